@@ -0,0 +1,73 @@
+use yadir::core::checked::ContainerBuilder;
+use yadir::core::contracts::DIBuilder;
+use yadir::core::primitives::{DIObj, Lifetime};
+use yadir::{deps, DIBuilder};
+
+#[derive(DIBuilder)]
+#[di(sync)]
+struct Config {
+    url: String,
+}
+
+#[derive(Clone, DIBuilder)]
+struct Pool {
+    #[deps]
+    config: DIObj<Config>,
+}
+
+#[derive(Clone, DIBuilder)]
+struct Cache {
+    #[di(default = 64)]
+    capacity: usize,
+}
+
+#[derive(Clone)]
+struct Service {
+    pool: Pool,
+    cache: Cache,
+}
+
+struct ServiceBuilder;
+
+impl DIBuilder for ServiceBuilder {
+    type Input = deps!(Pool, Cache);
+    type Output = Service;
+
+    async fn build((pool, cache): Self::Input) -> Self::Output {
+        Service {
+            pool: pool.extract(),
+            cache: cache.extract(),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_checked_container_for_registered_dependencies() {
+    let mut container = ContainerBuilder::new()
+        .register_sync::<Config, _>(Some(Lifetime::Singleton))
+        .register::<Cache, _>(None)
+        .await
+        .register::<Pool, _>(Some(Lifetime::Transient))
+        .await
+        .register::<ServiceBuilder, _>(None)
+        .await
+        .build();
+
+    let config = container.resolve_sync::<Config, _>().unwrap();
+    let service = container
+        .resolve::<ServiceBuilder, _>()
+        .await
+        .unwrap()
+        .extract();
+
+    config.write().unwrap().url = "postgres://localhost".to_string();
+
+    assert_eq!(
+        service.pool.config.read().unwrap().url,
+        "postgres://localhost"
+    );
+    assert_eq!(service.cache.capacity, 64);
+
+    let mut manager = container.into_manager();
+    assert!(manager.resolve::<Pool>().await.is_some());
+}
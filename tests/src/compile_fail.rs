@@ -0,0 +1,5 @@
+#[test]
+fn test_di_builder_proc_macro_for_invalid_attributes() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("ui/*.rs");
+}
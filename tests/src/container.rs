@@ -0,0 +1,108 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use yadir::container;
+use yadir::core::primitives::DIObj;
+use yadir::DIBuilder;
+
+static CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
+static SESSIONS: AtomicUsize = AtomicUsize::new(0);
+
+trait Printer: Sync + Send {
+    fn print(&self) -> String;
+}
+
+#[derive(Clone, DIBuilder)]
+#[build_as(Arc<dyn Printer>)]
+struct ConsolePrinter;
+
+impl Printer for ConsolePrinter {
+    fn print(&self) -> String {
+        "console".to_string()
+    }
+}
+
+#[derive(DIBuilder)]
+#[build_method(Self::connect)]
+#[di(sync)]
+struct DbPool {
+    id: usize,
+}
+
+impl DbPool {
+    fn connect() -> Self {
+        Self {
+            id: CONNECTIONS.fetch_add(1, Ordering::SeqCst),
+        }
+    }
+}
+
+#[derive(Clone, DIBuilder)]
+#[build_method(Self::open)]
+#[di(sync)]
+struct Session {
+    id: usize,
+}
+
+impl Session {
+    fn open() -> Self {
+        Self {
+            id: SESSIONS.fetch_add(1, Ordering::SeqCst),
+        }
+    }
+}
+
+#[derive(Clone, DIBuilder)]
+struct HTTPClient {
+    #[deps]
+    printer: Arc<dyn Printer>,
+}
+
+#[derive(Clone, DIBuilder)]
+struct UserService {
+    #[deps]
+    pool: DIObj<DbPool>,
+    #[deps]
+    client: HTTPClient,
+    #[deps]
+    session: Session,
+}
+
+container! {
+    /// The container of the services of the application.
+    pub struct AppContainer {
+        // declared before its dependencies on purpose
+        UserService: transient,
+        HTTPClient as client: transient,
+        DbPool: singleton,
+        Session: transient,
+        ConsolePrinter: singleton,
+    }
+}
+
+#[tokio::test]
+async fn test_container_macro_for_singletons_and_transients() {
+    let mut app = AppContainer::new().await;
+    assert_eq!(CONNECTIONS.load(Ordering::SeqCst), 1);
+    assert_eq!(SESSIONS.load(Ordering::SeqCst), 0);
+
+    let first = app.user_service().await.extract();
+    let second = app.user_service().await.extract();
+    assert_eq!(first.session.id, 0);
+    assert_eq!(second.session.id, 1);
+
+    // the singleton is shared by all the dependants and accessors
+    app.db_pool().await.write().unwrap().id = 42;
+    assert_eq!(first.pool.read().unwrap().id, 42);
+    assert_eq!(second.pool.read().unwrap().id, 42);
+    assert_eq!(CONNECTIONS.load(Ordering::SeqCst), 1);
+
+    assert_eq!(first.client.printer.print(), "console");
+    assert_eq!(
+        app.client().await.read().unwrap().printer.print(),
+        "console"
+    );
+    assert_eq!(
+        app.console_printer().await.read().unwrap().print(),
+        "console"
+    );
+}
@@ -0,0 +1,125 @@
+// nothing is imported on purpose, as the derive and the macros must not rely on what is in scope
+
+// shadows the standard `vec!` macro, which the generated code must not pick up
+#[allow(unused_macros)]
+macro_rules! vec {
+    ($( $tt:tt )*) => {
+        ::core::compile_error!("the generated code must use `::std::vec!`")
+    };
+}
+
+mod reexport {
+    pub use yadir as container;
+}
+
+#[derive(Clone, yadir::DIBuilder)]
+#[build_as(Box<dyn Greeter>)]
+struct English;
+
+trait Greeter: Send + Sync {
+    fn greet(&self) -> String;
+}
+
+impl Greeter for English {
+    fn greet(&self) -> String {
+        "hello".to_string()
+    }
+}
+
+#[derive(Clone, yadir::DIBuilder)]
+#[di(crate = "crate::hygiene::reexport::container")]
+struct Salutation {
+    #[deps]
+    greeter: yadir::core::primitives::DIObj<Box<dyn Greeter>>,
+    #[deps]
+    punctuation: Punctuation,
+}
+
+#[derive(Clone)]
+struct Punctuation(char);
+
+impl yadir::core::contracts::SyncDIBuilder for Punctuation {
+    type Input = yadir::deps!();
+    type Output = Self;
+
+    fn build(_: Self::Input) -> Self::Output {
+        Self('!')
+    }
+}
+
+struct Banner(String);
+
+impl yadir::core::contracts::SyncDIBuilder for Banner {
+    type Input = yadir::deps!(Salutation, Punctuation);
+    type Output = Self;
+
+    fn build(input: Self::Input) -> Self::Output {
+        yadir::let_deps!(salutation, punctuation <- input);
+
+        Self(format!("{}{}", salutation.greet(), punctuation.0))
+    }
+}
+
+// the inherent `default` method shadows `Default::default`, which the generated code must call instead
+#[derive(Clone, yadir::DIBuilder)]
+#[build_method("default")]
+struct Farewell(&'static str);
+
+impl Default for Farewell {
+    fn default() -> Self {
+        Self("bye")
+    }
+}
+
+impl Farewell {
+    fn default(language: &str) -> Self {
+        match language {
+            "fr" => Self("au revoir"),
+            _ => Self("bye"),
+        }
+    }
+}
+
+struct Signature(String);
+
+#[yadir::injectable]
+impl Signature {
+    fn new(farewell: Farewell) -> Self {
+        Self(farewell.0.to_string())
+    }
+}
+
+impl Salutation {
+    fn greet(&self) -> String {
+        format!(
+            "{}{}",
+            self.greeter.read().unwrap().greet(),
+            self.punctuation.0
+        )
+    }
+}
+
+#[tokio::test]
+async fn test_di_builder_proc_macro_without_imports() {
+    let mut manager = yadir::core::primitives::DIManager::default();
+    manager
+        .register::<English>(None)
+        .await
+        .register::<Punctuation>(None)
+        .await
+        .register::<Salutation>(None)
+        .await
+        .register::<Banner>(None)
+        .await
+        .register::<Farewell>(None)
+        .await
+        .register::<Signature>(None)
+        .await;
+
+    let banner = manager.resolve::<Banner>().await.unwrap();
+    assert_eq!(banner.read().unwrap().0, "hello!!");
+
+    let signature = manager.resolve::<Signature>().await.unwrap();
+    assert_eq!(signature.read().unwrap().0, "bye");
+    assert_eq!(Farewell::default("fr").0, "au revoir");
+}
@@ -1,13 +1,27 @@
+#[cfg(test)]
+mod checked;
+#[cfg(test)]
+mod compile_fail;
+#[cfg(test)]
+mod container;
+#[cfg(test)]
+mod hygiene;
+#[cfg(test)]
+mod registry;
+#[cfg(test)]
+mod runtime;
+#[cfg(test)]
+mod telemetry;
+
 #[cfg(test)]
 mod tests {
     #![allow(dead_code)]
 
-    use async_trait::async_trait;
     use claim::assert_some;
+    use std::sync::Arc;
     use yadir::core::contracts::DIBuilder;
-    use yadir::core::primitives::{DIManager, DIObj};
-    use yadir::DIBuilder;
-    use yadir::{deps, let_deps};
+    use yadir::core::primitives::{DIManager, DIObj, Lifetime, ResolveError};
+    use yadir::{deps, injectable, provider, DIBuilder};
 
     #[tokio::test]
     async fn test_di_builder_proc_macro_for_all_named_fields_as_deps() {
@@ -153,4 +167,954 @@ mod tests {
 
         assert_some!(foo);
     }
+
+    #[test]
+    fn test_di_builder_proc_macro_for_sync_builders() {
+        #[derive(Clone, DIBuilder)]
+        #[di(sync)]
+        struct Bar;
+
+        #[derive(Default, Clone, DIBuilder)]
+        #[di(sync)]
+        #[build_method("default")]
+        struct Baz;
+
+        #[derive(Clone, DIBuilder)]
+        #[di(sync)]
+        struct Foo {
+            #[deps]
+            bar: Bar,
+            #[deps]
+            baz: Baz,
+        }
+
+        let mut manager = DIManager::default();
+        manager
+            .register_sync::<Bar>(Some(Lifetime::Singleton))
+            .register_sync::<Baz>(None)
+            .register_sync::<Foo>(None);
+
+        assert_some!(manager.resolve_sync::<Foo>());
+    }
+
+    #[tokio::test]
+    async fn test_di_builder_proc_macro_for_mixing_sync_and_async_builders() {
+        #[derive(Clone, DIBuilder)]
+        struct Bar;
+
+        #[derive(Clone, DIBuilder)]
+        #[di(sync)]
+        struct Baz(#[deps] Bar);
+
+        #[derive(Clone, DIBuilder)]
+        struct Foo(#[deps] Baz);
+
+        let mut manager = DIManager::default();
+        manager
+            .register::<Bar>(Some(Lifetime::Singleton))
+            .await
+            .register_sync::<Baz>(Some(Lifetime::Transient))
+            .register::<Foo>(Some(Lifetime::Transient))
+            .await;
+
+        assert_some!(manager.resolve_sync::<Baz>());
+        assert_some!(manager.resolve::<Baz>().await);
+        assert_some!(manager.resolve::<Foo>().await);
+    }
+
+    #[tokio::test]
+    async fn test_di_builder_proc_macro_for_smart_pointer_outputs() {
+        trait Printer: Send + Sync {
+            fn print(&self) -> String;
+        }
+
+        #[derive(DIBuilder)]
+        #[build_as(Arc<dyn Printer>)]
+        struct Bar;
+
+        impl Printer for Bar {
+            fn print(&self) -> String {
+                "bar".to_string()
+            }
+        }
+
+        #[derive(DIBuilder)]
+        #[build_as(Box<dyn Printer>)]
+        struct Foo(#[deps] Arc<dyn Printer>);
+
+        impl Printer for Foo {
+            fn print(&self) -> String {
+                format!("foo {}", self.0.print())
+            }
+        }
+
+        let mut manager = DIManager::default();
+        manager
+            .register::<Bar>(Some(Lifetime::Singleton))
+            .await
+            .register::<Foo>(Some(Lifetime::Transient))
+            .await;
+
+        let foo = manager.resolve::<Foo>().await.unwrap();
+        assert_eq!(foo.read().unwrap().print(), "foo bar");
+    }
+
+    #[tokio::test]
+    async fn test_di_builder_proc_macro_for_converted_outputs() {
+        #[derive(Clone, DIBuilder)]
+        #[build_as(Port)]
+        #[build_method("default")]
+        struct Config {
+            port: u16,
+        }
+
+        impl Default for Config {
+            fn default() -> Self {
+                Self { port: 8080 }
+            }
+        }
+
+        #[derive(Clone, Debug, PartialEq)]
+        struct Port(u16);
+
+        impl From<Config> for Port {
+            fn from(config: Config) -> Self {
+                Self(config.port)
+            }
+        }
+
+        let mut manager = DIManager::default();
+        manager.register::<Config>(None).await;
+
+        assert_eq!(
+            manager.resolve::<Config>().await.unwrap().extract(),
+            Port(8080)
+        );
+        assert_eq!(Config::metadata().output.name, "Port");
+    }
+
+    #[tokio::test]
+    async fn test_di_builder_proc_macro_for_multiple_outputs() {
+        trait Printer: Send + Sync {
+            fn print(&self) -> String;
+        }
+
+        trait Writer: Send + Sync {
+            fn write(&self) -> String;
+        }
+
+        #[derive(DIBuilder)]
+        #[build_as(Arc<dyn Printer>, Arc<dyn Writer>, Box<dyn Writer>)]
+        struct Console(#[deps] Bar);
+
+        impl Printer for Console {
+            fn print(&self) -> String {
+                "print".to_string()
+            }
+        }
+
+        impl Writer for Console {
+            fn write(&self) -> String {
+                "write".to_string()
+            }
+        }
+
+        #[derive(Clone, DIBuilder)]
+        struct Bar;
+
+        let mut manager = DIManager::default();
+        manager
+            .register::<Bar>(None)
+            .await
+            .register::<Console>(Some(Lifetime::Singleton))
+            .await;
+
+        let printer = manager.resolve::<Console>().await.unwrap();
+        let writer = manager
+            .resolve_as::<Console, Arc<dyn Writer>>()
+            .await
+            .unwrap();
+        let boxed = manager
+            .resolve_as::<Console, Box<dyn Writer>>()
+            .await
+            .unwrap();
+
+        assert_eq!(printer.read().unwrap().print(), "print");
+        assert_eq!(writer.read().unwrap().write(), "write");
+        assert_eq!(boxed.read().unwrap().write(), "write");
+        assert!(writer.ptr_eq(
+            &manager
+                .resolve_as::<Console, Arc<dyn Writer>>()
+                .await
+                .unwrap()
+        ));
+
+        let outputs = manager
+            .registrations()
+            .into_iter()
+            .filter(|registration| registration.type_name == "Console")
+            .map(|registration| registration.output.name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outputs,
+            ["Arc<dyn Printer>", "Arc<dyn Writer>", "Box<dyn Writer>"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_di_builder_proc_macro_for_type_generics() {
+        trait Repo: Send + Sync {
+            fn name(&self) -> String;
+        }
+
+        #[derive(Clone, DIBuilder)]
+        struct SqlRepo;
+
+        impl Repo for SqlRepo {
+            fn name(&self) -> String {
+                "sql".to_string()
+            }
+        }
+
+        #[derive(Clone, DIBuilder)]
+        struct Service<R: Repo>
+        where
+            R: Clone,
+        {
+            #[deps]
+            repo: R,
+        }
+
+        #[derive(DIBuilder)]
+        #[build_as(Box<dyn Repo>)]
+        struct Cached<R: Repo + Clone>(#[deps] DIObj<R>);
+
+        impl<R: Repo + Clone> Repo for Cached<R> {
+            fn name(&self) -> String {
+                format!("cached {}", self.0.read().unwrap().name())
+            }
+        }
+
+        let mut manager = DIManager::default();
+        manager
+            .register::<SqlRepo>(Some(Lifetime::Singleton))
+            .await
+            .register::<Service<SqlRepo>>(None)
+            .await
+            .register::<Cached<SqlRepo>>(None)
+            .await;
+
+        let service = manager.resolve::<Service<SqlRepo>>().await.unwrap();
+        let cached = manager.resolve::<Cached<SqlRepo>>().await.unwrap();
+
+        assert_eq!(service.extract().repo.name(), "sql");
+        assert_eq!(cached.read().unwrap().name(), "cached sql");
+
+        let metadata = Service::<SqlRepo>::metadata();
+        assert!(metadata.type_name.contains("Service<"));
+        assert!(metadata.type_name.ends_with("SqlRepo>"));
+        assert_eq!(
+            metadata.dependencies[0].id,
+            std::any::TypeId::of::<SqlRepo>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_di_builder_proc_macro_for_const_generics() {
+        #[derive(Clone, DIBuilder)]
+        #[build_method("default")]
+        struct Buffer<const N: usize>([u8; N]);
+
+        impl<const N: usize> Default for Buffer<N> {
+            fn default() -> Self {
+                Self([0; N])
+            }
+        }
+
+        #[derive(Clone, DIBuilder)]
+        struct Reader<const N: usize> {
+            #[deps]
+            buffer: Buffer<N>,
+        }
+
+        let mut manager = DIManager::default();
+        manager
+            .register::<Buffer<4>>(None)
+            .await
+            .register::<Buffer<8>>(None)
+            .await
+            .register::<Reader<8>>(None)
+            .await;
+
+        assert_eq!(
+            manager
+                .resolve::<Reader<8>>()
+                .await
+                .unwrap()
+                .extract()
+                .buffer
+                .0
+                .len(),
+            8
+        );
+        assert!(manager.resolve::<Reader<4>>().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_di_builder_proc_macro_for_constructor_paths_in_declared_order() {
+        #[derive(Clone, DIBuilder)]
+        struct Pool;
+
+        #[derive(Clone, DIBuilder)]
+        struct Config;
+
+        #[derive(Clone, DIBuilder)]
+        #[build_method(Self::with_pool, args(pool, config))]
+        struct Repository {
+            #[deps]
+            config: Config,
+            #[deps]
+            pool: Pool,
+        }
+
+        impl Repository {
+            fn with_pool(pool: Pool, config: Config) -> Self {
+                Self { config, pool }
+            }
+        }
+
+        #[derive(Clone, DIBuilder)]
+        #[build_method(make_cache, args(1, 0))]
+        struct Cache(#[deps] Config, #[deps] Pool);
+
+        fn make_cache(pool: Pool, config: Config) -> Cache {
+            Cache(config, pool)
+        }
+
+        let mut manager = DIManager::default();
+        manager
+            .register::<Pool>(None)
+            .await
+            .register::<Config>(None)
+            .await
+            .register::<Repository>(None)
+            .await
+            .register::<Cache>(None)
+            .await;
+
+        assert_some!(manager.resolve::<Repository>().await);
+        assert_some!(manager.resolve::<Cache>().await);
+    }
+
+    #[tokio::test]
+    async fn test_di_builder_proc_macro_for_async_constructors() {
+        #[derive(Clone)]
+        struct Config {
+            url: String,
+        }
+
+        #[derive(Clone, DIBuilder)]
+        #[build_method(async = "connect")]
+        struct Connection {
+            #[deps]
+            config: Config,
+            handshakes: usize,
+        }
+
+        impl Connection {
+            async fn connect(config: Config) -> Self {
+                tokio::task::yield_now().await;
+
+                Self {
+                    config,
+                    handshakes: 1,
+                }
+            }
+        }
+
+        #[derive(Clone, DIBuilder)]
+        #[build_method(async = Self::open)]
+        struct Session;
+
+        impl Session {
+            async fn open() -> Self {
+                tokio::task::yield_now().await;
+                Self
+            }
+        }
+
+        impl Default for Config {
+            fn default() -> Self {
+                Self {
+                    url: "postgres://localhost".to_string(),
+                }
+            }
+        }
+
+        #[derive(Clone, DIBuilder)]
+        #[di(sync)]
+        #[build_as(Config)]
+        #[build_method("default")]
+        struct ConfigBuilder;
+
+        impl From<ConfigBuilder> for Config {
+            fn from(_: ConfigBuilder) -> Self {
+                Config::default()
+            }
+        }
+
+        impl Default for ConfigBuilder {
+            fn default() -> Self {
+                Self
+            }
+        }
+
+        let mut manager = DIManager::default();
+        manager.build_sync::<ConfigBuilder>();
+        manager
+            .register::<Connection>(Some(Lifetime::Singleton))
+            .await
+            .register::<Session>(None)
+            .await;
+
+        let connection = manager.resolve::<Connection>().await.unwrap().extract();
+        assert_eq!(connection.config.url, "postgres://localhost");
+        assert_eq!(connection.handshakes, 1);
+        assert_some!(manager.resolve::<Session>().await);
+    }
+
+    #[tokio::test]
+    async fn test_di_builder_proc_macro_for_mixed_injected_and_defaulted_fields() {
+        use std::collections::HashMap;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Clone, DIBuilder)]
+        struct Pool;
+
+        #[derive(Clone, DIBuilder)]
+        struct Repository {
+            #[deps]
+            pool: Pool,
+            #[di(default = NEXT_ID.fetch_add(1, Ordering::SeqCst))]
+            id: usize,
+            #[di(default = "users".to_string())]
+            table: String,
+            cache: HashMap<String, String>,
+            hits: usize,
+        }
+
+        #[derive(Clone, DIBuilder)]
+        struct Counter(#[deps] Pool, usize, #[di(default = 10)] usize);
+
+        // neither the struct nor its dependencies implement `Default`
+        #[derive(Clone, DIBuilder)]
+        #[build_method("default")]
+        struct Service {
+            #[deps]
+            repository: Repository,
+            #[di(default = 3)]
+            retries: u8,
+        }
+
+        let mut manager = DIManager::default();
+        manager
+            .register::<Pool>(Some(Lifetime::Singleton))
+            .await
+            .register::<Repository>(None)
+            .await
+            .register::<Counter>(None)
+            .await
+            .register::<Service>(None)
+            .await;
+
+        let first = manager.resolve::<Repository>().await.unwrap().extract();
+        let second = manager.resolve::<Repository>().await.unwrap().extract();
+        assert_ne!(first.id, second.id);
+        assert_eq!(first.table, "users");
+        assert!(first.cache.is_empty());
+        assert_eq!(first.hits, 0);
+
+        let counter = manager.resolve::<Counter>().await.unwrap().extract();
+        assert_eq!((counter.1, counter.2), (0, 10));
+
+        let service = manager.resolve::<Service>().await.unwrap().extract();
+        assert_eq!(service.retries, 3);
+        assert_eq!(service.repository.table, "users");
+    }
+
+    #[tokio::test]
+    async fn test_injectable_constructors() {
+        trait Repo: Send + Sync {
+            fn find(&self) -> String;
+        }
+
+        struct SqlRepo {
+            table: String,
+        }
+
+        impl Repo for SqlRepo {
+            fn find(&self) -> String {
+                format!("select * from {}", self.table)
+            }
+        }
+
+        #[derive(Clone)]
+        struct Config {
+            table: String,
+            retries: u8,
+        }
+
+        #[injectable]
+        impl Config {
+            fn new() -> Self {
+                Self {
+                    table: "users".to_string(),
+                    retries: 3,
+                }
+            }
+        }
+
+        // the constructor takes things which are not stored as fields
+        #[injectable]
+        impl SqlRepo {
+            fn table(&self) -> &str {
+                &self.table
+            }
+
+            #[injectable]
+            fn connect(config: Config) -> Box<dyn Repo> {
+                Box::new(Self {
+                    table: config.table,
+                })
+            }
+        }
+
+        struct Service {
+            query: String,
+            retries: u8,
+        }
+
+        #[injectable]
+        impl Service {
+            #[injectable]
+            async fn connect(repo: DIObj<Box<dyn Repo>>, config: Config) -> Result<Self, String> {
+                tokio::task::yield_now().await;
+
+                match config.retries {
+                    0 => Err("no retries left".to_string()),
+                    retries => Ok(Self {
+                        query: repo.read().map_err(|e| e.to_string())?.find(),
+                        retries,
+                    }),
+                }
+            }
+        }
+
+        let mut manager = DIManager::default();
+        manager
+            .register::<Config>(Some(Lifetime::Singleton))
+            .await
+            .register::<SqlRepo>(None)
+            .await
+            .register::<Service>(None)
+            .await;
+
+        let service = manager.resolve::<Service>().await.unwrap();
+        let service = service.read().unwrap();
+        assert_eq!(service.query, "select * from users");
+        assert_eq!(service.retries, 3);
+
+        let metadata = <Service as DIBuilder>::metadata();
+        assert_eq!(metadata.type_name, "Service");
+        assert_eq!(metadata.output.name, "Service");
+        assert_eq!(
+            metadata
+                .dependencies
+                .iter()
+                .map(|dependency| dependency.name)
+                .collect::<Vec<_>>(),
+            vec!["Box<dyn Repo>", "Config"]
+        );
+
+        // the error of the constructor fails the build instead of being registered as the output
+        manager.replace_singleton::<Config>(Config {
+            table: "users".to_string(),
+            retries: 0,
+        });
+        assert_eq!(
+            manager.try_resolve::<Service>().await.err(),
+            Some(ResolveError::Failed)
+        );
+        assert!(manager.resolve::<Service>().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_injectable_fallible_constructors() {
+        use std::sync::Mutex;
+        use yadir::core::contracts::Interceptor;
+        use yadir::core::introspection::BuilderMetadata;
+
+        #[derive(Default)]
+        struct Errors(Arc<Mutex<Vec<(&'static str, ResolveError)>>>);
+
+        impl Interceptor for Errors {
+            fn on_resolve_error(&self, metadata: &BuilderMetadata, error: &ResolveError) {
+                self.0.lock().unwrap().push((metadata.type_name, *error));
+            }
+        }
+
+        struct Port(u16);
+
+        #[injectable]
+        impl Port {
+            fn parse() -> std::io::Result<Self> {
+                "8080"
+                    .parse()
+                    .map(Self)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+            }
+        }
+
+        struct Secret(String);
+
+        #[injectable]
+        impl Secret {
+            fn load() -> Result<Self, std::env::VarError> {
+                Err(std::env::VarError::NotPresent)
+            }
+        }
+
+        let errors = Errors::default();
+        let recorded = errors.0.clone();
+        let mut manager = DIManager::default();
+        manager
+            .add_interceptor(errors)
+            .register_sync::<Port>(Some(Lifetime::Singleton))
+            .register_sync::<Secret>(Some(Lifetime::Singleton));
+
+        assert_eq!(
+            manager.resolve_sync::<Port>().unwrap().read().unwrap().0,
+            8080
+        );
+        assert_eq!(<Port as DIBuilder>::metadata().output.name, "Port");
+
+        // a failed singleton is left unregistered, without panicking
+        assert!(!manager.has::<DIObj<Secret>>());
+        assert_eq!(
+            manager.try_resolve_sync::<Secret>().err(),
+            Some(ResolveError::NotRegistered)
+        );
+        assert_eq!(
+            *recorded.lock().unwrap(),
+            [
+                ("Secret", ResolveError::Failed),
+                ("Secret", ResolveError::NotRegistered)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_injectable_generic_constructors() {
+        trait Store: Send + Sync {
+            fn name(&self) -> &'static str;
+        }
+
+        #[derive(Clone)]
+        struct Memory;
+
+        impl Store for Memory {
+            fn name(&self) -> &'static str {
+                "memory"
+            }
+        }
+
+        #[injectable]
+        impl Memory {
+            fn new() -> Self {
+                Self
+            }
+        }
+
+        struct Cache<S> {
+            store: S,
+        }
+
+        #[injectable]
+        impl<S: Store + Clone> Cache<S> {
+            #[injectable]
+            fn new(store: S) -> Self {
+                Self { store }
+            }
+
+            fn name(&self) -> &'static str {
+                self.store.name()
+            }
+        }
+
+        let mut manager = DIManager::default();
+        manager
+            .register_sync::<Memory>(None)
+            .register_sync::<Cache<Memory>>(None);
+
+        let cache = manager.resolve_sync::<Cache<Memory>>().unwrap();
+        assert_eq!(cache.read().unwrap().name(), "memory");
+    }
+
+    #[tokio::test]
+    async fn test_provider_functions() {
+        use std::collections::HashMap;
+        use std::sync::Mutex;
+
+        trait Store: Send + Sync {
+            fn get(&self, key: &str) -> Option<String>;
+        }
+
+        impl Store for Mutex<HashMap<String, String>> {
+            fn get(&self, key: &str) -> Option<String> {
+                self.lock().unwrap().get(key).cloned()
+            }
+        }
+
+        #[derive(Clone)]
+        struct Options {
+            seed: Vec<(String, String)>,
+        }
+
+        #[provider]
+        fn make_options() -> Options {
+            Options {
+                seed: vec![("greeting".to_string(), "hello".to_string())],
+            }
+        }
+
+        #[provider(lifetime = "singleton")]
+        async fn make_store(options: Options) -> Box<dyn Store> {
+            tokio::task::yield_now().await;
+
+            Box::new(Mutex::new(
+                options.seed.into_iter().collect::<HashMap<_, _>>(),
+            ))
+        }
+
+        // a provider may depend on other providers through their outputs
+        #[provider]
+        fn make_greeting(store: DIObj<Box<dyn Store>>) -> String {
+            store.read().unwrap().get("greeting").unwrap_or_default()
+        }
+
+        let mut manager = DIManager::default();
+        manager
+            .register_provider(make_options)
+            .await
+            .register_provider(make_store)
+            .await
+            .register_provider(make_greeting)
+            .await;
+
+        let greeting = manager.resolve::<make_greeting>().await.unwrap().extract();
+        assert_eq!(greeting, "hello");
+
+        let first = manager.resolve::<make_store>().await.unwrap();
+        let second = manager.resolve::<make_store>().await.unwrap();
+        assert!(first.ptr_eq(&second));
+
+        let lifetimes = manager
+            .registrations()
+            .into_iter()
+            .map(|registration| (registration.type_name, registration.lifetime))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lifetimes,
+            vec![
+                ("make_options", Lifetime::Transient),
+                ("make_store", Lifetime::Singleton),
+                ("make_greeting", Lifetime::Transient),
+            ]
+        );
+
+        // the body of a provider can still be called directly
+        assert_eq!(make_options::provide().seed.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_di_builder_proc_macro_for_mapped_deps() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        #[derive(Clone, DIBuilder)]
+        struct Config {
+            #[di(default = Duration::from_secs(5))]
+            timeout: Duration,
+            #[di(default = 8080)]
+            port: u16,
+        }
+
+        #[derive(DIBuilder)]
+        struct Pool {
+            #[di(default = AtomicUsize::new(0))]
+            opened: AtomicUsize,
+        }
+
+        struct Connection {
+            id: usize,
+        }
+
+        impl Pool {
+            fn connect(pool: DIObj<Pool>) -> Connection {
+                let pool = pool.read().unwrap();
+
+                Connection {
+                    id: pool.opened.fetch_add(1, Ordering::SeqCst),
+                }
+            }
+        }
+
+        #[derive(Clone, Copy, PartialEq, Debug)]
+        struct Port(u16);
+
+        impl From<Config> for Port {
+            fn from(config: Config) -> Self {
+                Port(config.port)
+            }
+        }
+
+        #[derive(DIBuilder)]
+        struct Client {
+            #[deps(from = Config, with = |config: Config| config.timeout)]
+            timeout: Duration,
+            #[deps(from = DIObj<Pool>, with = Pool::connect)]
+            connection: Connection,
+            #[deps(from = Config)]
+            port: Port,
+        }
+
+        #[derive(DIBuilder)]
+        #[build_method("new", args(1, 0))]
+        struct Server(#[deps(from = Config)] Port, #[deps] DIObj<Pool>);
+
+        impl Server {
+            fn new(pool: DIObj<Pool>, port: Port) -> Self {
+                Self(port, pool)
+            }
+        }
+
+        let mut manager = DIManager::default();
+        manager
+            .register::<Config>(None)
+            .await
+            .register::<Pool>(Some(Lifetime::Singleton))
+            .await
+            .register::<Client>(None)
+            .await
+            .register::<Server>(None)
+            .await;
+
+        let first = manager.resolve::<Client>().await.unwrap();
+        let second = manager.resolve::<Client>().await.unwrap();
+        let (first, second) = (first.read().unwrap(), second.read().unwrap());
+        assert_eq!(first.timeout, Duration::from_secs(5));
+        assert_eq!(first.port, Port(8080));
+        assert_eq!((first.connection.id, second.connection.id), (1, 2));
+
+        let server = manager.resolve::<Server>().await.unwrap();
+        assert_eq!(server.read().unwrap().0, Port(8080));
+
+        // the dependencies are the types resolved from the container rather than the types of the fields
+        let dependencies = <Client as DIBuilder>::metadata()
+            .dependencies
+            .iter()
+            .map(|dependency| dependency.name)
+            .collect::<Vec<_>>();
+        assert_eq!(dependencies, vec!["Config", "Pool", "Config"]);
+    }
+
+    #[tokio::test]
+    async fn test_di_builder_proc_macro_for_flat_and_nested_inputs() {
+        #[derive(Clone, DIBuilder)]
+        struct Dep<const N: usize>;
+
+        #[derive(Clone, DIBuilder)]
+        struct Small {
+            #[deps]
+            first: Dep<0>,
+            #[deps]
+            second: DIObj<Dep<1>>,
+        }
+
+        // beyond 16 dependencies, the derived input falls back to nested pairs
+        #[derive(Clone, DIBuilder)]
+        struct Large(
+            #[deps] Dep<0>,
+            #[deps] Dep<1>,
+            #[deps] Dep<2>,
+            #[deps] Dep<3>,
+            #[deps] Dep<4>,
+            #[deps] Dep<5>,
+            #[deps] Dep<6>,
+            #[deps] Dep<7>,
+            #[deps] Dep<8>,
+            #[deps] Dep<9>,
+            #[deps] Dep<10>,
+            #[deps] Dep<11>,
+            #[deps] Dep<12>,
+            #[deps] Dep<13>,
+            #[deps] Dep<14>,
+            #[deps] Dep<15>,
+            #[deps] DIObj<Dep<16>>,
+        );
+
+        fn input_of<T: DIBuilder<Input = I>, I>() {}
+        input_of::<Small, (DIObj<Dep<0>>, DIObj<Dep<1>>)>();
+        input_of::<Small, deps!(Dep<0>, Dep<1>)>();
+
+        let mut manager = DIManager::default();
+        manager
+            .register::<Dep<0>>(None)
+            .await
+            .register::<Dep<1>>(None)
+            .await
+            .register::<Dep<2>>(None)
+            .await
+            .register::<Dep<3>>(None)
+            .await
+            .register::<Dep<4>>(None)
+            .await
+            .register::<Dep<5>>(None)
+            .await
+            .register::<Dep<6>>(None)
+            .await
+            .register::<Dep<7>>(None)
+            .await
+            .register::<Dep<8>>(None)
+            .await
+            .register::<Dep<9>>(None)
+            .await
+            .register::<Dep<10>>(None)
+            .await
+            .register::<Dep<11>>(None)
+            .await
+            .register::<Dep<12>>(None)
+            .await
+            .register::<Dep<13>>(None)
+            .await
+            .register::<Dep<14>>(None)
+            .await
+            .register::<Dep<15>>(None)
+            .await
+            .register::<Dep<16>>(Some(Lifetime::Singleton))
+            .await
+            .register::<Small>(None)
+            .await
+            .register::<Large>(None)
+            .await;
+
+        assert_some!(manager.resolve::<Small>().await);
+        let large = manager.resolve::<Large>().await.unwrap().extract();
+        assert!(large
+            .16
+            .ptr_eq(&manager.resolve::<Dep<16>>().await.unwrap()));
+        assert_eq!(<Large as DIBuilder>::metadata().dependencies.len(), 17);
+    }
 }
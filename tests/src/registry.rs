@@ -0,0 +1,74 @@
+use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+use yadir::core::registry::Component;
+use yadir::DIBuilder;
+
+// the dependants are submitted before their dependencies on purpose
+#[derive(Clone, DIBuilder)]
+#[di(auto_register)]
+struct UserService {
+    #[deps]
+    repository: UserRepository,
+}
+
+#[derive(Clone, DIBuilder)]
+#[di(lifetime = "transient", auto_register)]
+struct UserRepository {
+    #[deps]
+    pool: DIObj<Pool>,
+}
+
+#[derive(DIBuilder)]
+#[di(sync, lifetime = "singleton", auto_register)]
+struct Pool;
+
+#[derive(Clone, DIBuilder)]
+struct Unregistered;
+
+#[tokio::test]
+async fn test_di_manager_for_registering_components_automatically() {
+    // the registration can be driven by a spawned task
+    let mut manager = tokio::spawn(DIManager::from_registered_components())
+        .await
+        .unwrap();
+
+    assert!(manager.has::<DIObj<Pool>>());
+    assert!(manager.has::<DIObj<UserRepository>>());
+    assert!(manager.has::<DIObj<UserService>>());
+    assert!(!manager.has::<DIObj<Unregistered>>());
+
+    let lifetimes = manager
+        .registrations()
+        .into_iter()
+        .map(|registration| (registration.type_name, registration.lifetime))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lifetimes,
+        vec![
+            ("Pool", Lifetime::Singleton),
+            ("UserRepository", Lifetime::Transient),
+            ("UserService", Lifetime::Transient),
+        ]
+    );
+
+    let first = manager.resolve::<UserService>().await.unwrap().extract();
+    let second = manager.resolve::<UserService>().await.unwrap().extract();
+    assert!(first.repository.pool.ptr_eq(&second.repository.pool));
+}
+
+#[test]
+fn test_registry_for_describing_submitted_components() {
+    let mut components = yadir::core::registry::inventory::iter::<Component>
+        .into_iter()
+        .map(|component| (component.metadata().type_name, component.lifetime()))
+        .collect::<Vec<_>>();
+    components.sort_by_key(|(type_name, _)| *type_name);
+
+    assert_eq!(
+        components,
+        vec![
+            ("Pool", Some(Lifetime::Singleton)),
+            ("UserRepository", Some(Lifetime::Transient)),
+            ("UserService", None),
+        ]
+    );
+}
@@ -0,0 +1,53 @@
+use claim::{assert_err, assert_ok};
+use std::time::Duration;
+use yadir::core::contracts::DIBuilder;
+use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+use yadir::deps;
+
+#[derive(Clone)]
+struct SlowBackend;
+
+impl DIBuilder for SlowBackend {
+    type Input = deps!();
+    type Output = Self;
+
+    async fn build(_: Self::Input) -> Self::Output {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        Self
+    }
+}
+
+#[tokio::test]
+async fn test_di_manager_for_registering_with_timeout() {
+    let mut manager = DIManager::default();
+
+    let registered = manager
+        .register_with_timeout::<SlowBackend>(Some(Lifetime::Singleton), Duration::from_millis(10))
+        .await;
+    assert_err!(registered);
+    assert!(!manager.has::<DIObj<SlowBackend>>());
+
+    let registered = manager
+        .register_with_timeout::<SlowBackend>(Some(Lifetime::Singleton), Duration::from_secs(5))
+        .await;
+    assert_ok!(registered);
+    assert!(manager.has::<DIObj<SlowBackend>>());
+}
+
+#[tokio::test]
+async fn test_di_manager_for_resolving_with_timeout() {
+    let mut manager = DIManager::default();
+    manager
+        .register::<SlowBackend>(Some(Lifetime::Transient))
+        .await;
+
+    let resolved = manager
+        .resolve_with_timeout::<SlowBackend>(Duration::from_millis(10))
+        .await;
+    assert!(resolved.is_err());
+
+    let resolved = manager
+        .resolve_with_timeout::<SlowBackend>(Duration::from_secs(5))
+        .await;
+    assert!(resolved.unwrap().is_some());
+}
@@ -0,0 +1,125 @@
+#![allow(dead_code)]
+
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+use metrics_util::MetricKind;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{Layer, Registry};
+use yadir::core::primitives::{DIManager, Lifetime};
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+struct Bar;
+
+#[derive(Clone, DIBuilder)]
+struct Foo(#[deps] Bar);
+
+/// A span recorded as `name type_name (parent name)`.
+#[derive(Clone, Default)]
+struct SpanRecorder(Arc<Mutex<Vec<String>>>);
+
+struct TypeNameVisitor(String);
+
+impl Visit for TypeNameVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "type_name" {
+            self.0 = value.rsplit("::").next().unwrap_or(value).to_string();
+        }
+    }
+
+    fn record_debug(&mut self, _: &Field, _: &dyn std::fmt::Debug) {}
+}
+
+impl<S> Layer<S> for SpanRecorder
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut visitor = TypeNameVisitor(String::new());
+        attrs.record(&mut visitor);
+
+        let parent = ctx
+            .span(id)
+            .and_then(|span| span.parent())
+            .map(|parent| parent.name())
+            .unwrap_or("root");
+
+        self.0.lock().unwrap().push(format!(
+            "{} {} ({})",
+            attrs.metadata().name(),
+            visitor.0,
+            parent
+        ));
+    }
+}
+
+#[tokio::test]
+async fn test_tracing_spans_for_builds_nested_in_resolves_and_injections_nested_in_builds() {
+    let recorder = SpanRecorder::default();
+    let _guard = tracing::subscriber::set_default(Registry::default().with(recorder.clone()));
+
+    let mut manager = DIManager::default();
+    manager
+        .register::<Bar>(Some(Lifetime::Singleton))
+        .await
+        .register::<Foo>(Some(Lifetime::Transient))
+        .await;
+
+    manager.resolve::<Bar>().await;
+    manager.resolve::<Foo>().await;
+
+    assert_eq!(
+        *recorder.0.lock().unwrap(),
+        [
+            "build Bar (root)",
+            "build Foo (root)",
+            "inject Bar (build)",
+            "resolve Bar (root)",
+            "resolve Foo (root)",
+            "build Foo (resolve)",
+            "inject Bar (build)",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_metrics_for_builds_resolves_and_failures() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    let _guard = metrics::set_default_local_recorder(&recorder);
+
+    let mut manager = DIManager::default();
+    manager.register::<Bar>(Some(Lifetime::Singleton)).await;
+
+    manager.resolve::<Bar>().await;
+    manager.resolve::<Foo>().await;
+
+    let snapshot = snapshotter.snapshot().into_vec();
+    let value = |name: &str| {
+        snapshot
+            .iter()
+            .find(|(key, _, _, _)| key.key().name() == name)
+            .map(|(key, _, _, value)| (key.kind(), value))
+    };
+
+    assert_eq!(
+        value("yadir_builds_total"),
+        Some((MetricKind::Counter, &DebugValue::Counter(1)))
+    );
+    assert!(matches!(
+        value("yadir_build_duration_seconds"),
+        Some((MetricKind::Histogram, DebugValue::Histogram(values))) if values.len() == 1
+    ));
+    assert_eq!(
+        value("yadir_resolves_total"),
+        Some((MetricKind::Counter, &DebugValue::Counter(1)))
+    );
+    assert_eq!(
+        value("yadir_resolve_failures_total"),
+        Some((MetricKind::Counter, &DebugValue::Counter(1)))
+    );
+}
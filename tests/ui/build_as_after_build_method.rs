@@ -0,0 +1,8 @@
+use yadir::DIBuilder;
+
+#[derive(Clone, Default, DIBuilder)]
+#[build_method("default")]
+#[build_as(Box<Foo>)]
+struct Foo;
+
+fn main() {}
@@ -0,0 +1,7 @@
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+#[build_as(Box<dyn>)]
+struct Foo;
+
+fn main() {}
@@ -0,0 +1,8 @@
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+#[build_as(Box<Foo>)]
+#[build_as(Box<Foo>)]
+struct Foo;
+
+fn main() {}
@@ -0,0 +1,11 @@
+use yadir::DIBuilder;
+
+trait Printer {}
+
+impl Printer for Foo {}
+
+#[derive(Clone, DIBuilder)]
+#[build_as(std::rc::Rc<dyn Printer>)]
+struct Foo;
+
+fn main() {}
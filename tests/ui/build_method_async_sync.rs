@@ -0,0 +1,14 @@
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+#[di(sync)]
+#[build_method(async = "connect")]
+struct Foo;
+
+impl Foo {
+    async fn connect() -> Self {
+        Self
+    }
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+#[build_method("new foo")]
+struct Foo;
+
+fn main() {}
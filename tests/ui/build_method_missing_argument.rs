@@ -0,0 +1,21 @@
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+struct Bar;
+
+#[derive(Clone, DIBuilder)]
+#[build_method("new", args(bar))]
+struct Foo {
+    #[deps]
+    bar: Bar,
+    #[deps]
+    baz: Bar,
+}
+
+impl Foo {
+    fn new(bar: Bar, baz: Bar) -> Self {
+        Self { bar, baz }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+#[build_method("new", arguments())]
+struct Foo;
+
+impl Foo {
+    fn new() -> Self {
+        Self
+    }
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use yadir::core::checked::ContainerBuilder;
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+#[di(sync)]
+struct Bar;
+
+#[derive(Clone, DIBuilder)]
+#[di(sync)]
+struct Foo(#[deps] Bar);
+
+fn main() {
+    ContainerBuilder::new().register_sync::<Foo, _>(None);
+}
@@ -0,0 +1,23 @@
+use yadir::core::checked::ContainerBuilder;
+use yadir::core::contracts::SyncDIBuilder;
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+#[di(sync)]
+struct Foo;
+
+struct OtherFoo;
+
+impl SyncDIBuilder for OtherFoo {
+    type Input = ();
+    type Output = Foo;
+
+    fn build(_: Self::Input) -> Self::Output {
+        Foo
+    }
+}
+
+fn main() {
+    let mut container = ContainerBuilder::new().register_sync::<Foo, _>(None).build();
+    container.resolve_sync::<OtherFoo, _>();
+}
@@ -0,0 +1,10 @@
+use yadir::core::checked::ContainerBuilder;
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+#[di(sync)]
+struct Foo;
+
+fn main() {
+    ContainerBuilder::new().build().resolve_sync::<Foo, _>();
+}
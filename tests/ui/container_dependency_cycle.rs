@@ -0,0 +1,17 @@
+use yadir::core::primitives::DIObj;
+use yadir::{container, DIBuilder};
+
+#[derive(DIBuilder)]
+struct A(#[deps] DIObj<B>);
+
+#[derive(DIBuilder)]
+struct B(#[deps] DIObj<A>);
+
+container! {
+    struct App {
+        A: transient,
+        B: transient,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+struct Bar;
+
+yadir::container! {
+    struct AppContainer {
+        Bar as shared: singleton,
+        Bar as fresh: transient,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+use yadir::DIBuilder;
+
+trait Printer: Send + Sync {}
+
+#[derive(Clone, DIBuilder)]
+#[build_as(Box<dyn Printer>)]
+struct Bar;
+
+impl Printer for Bar {}
+
+#[derive(Clone, DIBuilder)]
+#[build_as(Box<dyn Printer>)]
+struct Baz;
+
+impl Printer for Baz {}
+
+yadir::container! {
+    struct AppContainer {
+        Bar: singleton,
+        Baz: transient,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+struct Bar;
+
+#[derive(Clone, DIBuilder)]
+struct Foo(#[deps] Bar);
+
+yadir::container! {
+    struct AppContainer {
+        Foo: singleton,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+struct Foo;
+
+yadir::container! {
+    struct AppContainer {
+        Foo: scoped,
+    }
+}
+
+fn main() {}
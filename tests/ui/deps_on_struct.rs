@@ -0,0 +1,7 @@
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+#[deps]
+struct Foo;
+
+fn main() {}
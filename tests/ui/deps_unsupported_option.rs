@@ -0,0 +1,12 @@
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+struct Bar;
+
+#[derive(Clone, DIBuilder)]
+struct Foo {
+    #[deps(into = Bar)]
+    bar: Bar,
+}
+
+fn main() {}
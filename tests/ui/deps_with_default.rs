@@ -0,0 +1,13 @@
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+struct Bar;
+
+#[derive(Clone, DIBuilder)]
+struct Foo {
+    #[deps]
+    #[di(default = Bar)]
+    bar: Bar,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+#[di(lifetime = "scoped", auto_register)]
+struct Foo;
+
+fn main() {}
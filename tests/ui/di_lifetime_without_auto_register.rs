@@ -0,0 +1,7 @@
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+#[di(lifetime = "singleton")]
+struct Foo;
+
+fn main() {}
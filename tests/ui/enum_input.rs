@@ -0,0 +1,8 @@
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+enum Foo {
+    Bar,
+}
+
+fn main() {}
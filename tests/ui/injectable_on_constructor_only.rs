@@ -0,0 +1,10 @@
+struct Foo;
+
+impl Foo {
+    #[yadir::injectable]
+    fn new() -> Self {
+        Self
+    }
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+#[derive(Clone)]
+struct Foo;
+
+#[yadir::injectable]
+impl Foo {
+    fn new(&self) -> Self {
+        self.clone()
+    }
+}
+
+fn main() {}
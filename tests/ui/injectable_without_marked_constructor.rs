@@ -0,0 +1,14 @@
+struct Foo;
+
+#[yadir::injectable]
+impl Foo {
+    fn new() -> Self {
+        Self
+    }
+
+    fn empty() -> Self {
+        Self
+    }
+}
+
+fn main() {}
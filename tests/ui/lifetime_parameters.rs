@@ -0,0 +1,8 @@
+use yadir::DIBuilder;
+
+#[derive(Clone, DIBuilder)]
+struct Foo<'a> {
+    name: &'a str,
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+//! Providers are documented by the doc comments of their function.
+#![deny(missing_docs)]
+
+/// The options of the application.
+pub struct Options;
+
+/// Builds the options of the application.
+#[yadir::provider]
+pub fn make_options() -> Options {
+    Options
+}
+
+#[yadir::provider]
+pub fn make_other_options() -> Options {
+    Options
+}
+
+fn main() {}
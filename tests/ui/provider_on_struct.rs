@@ -0,0 +1,4 @@
+#[yadir::provider]
+struct Foo;
+
+fn main() {}
@@ -0,0 +1,8 @@
+struct Foo;
+
+#[yadir::provider(scope = "request")]
+fn make_foo() -> Foo {
+    Foo
+}
+
+fn main() {}
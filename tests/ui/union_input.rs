@@ -0,0 +1,8 @@
+use yadir::DIBuilder;
+
+#[derive(Clone, Copy, DIBuilder)]
+union Foo {
+    bar: u32,
+}
+
+fn main() {}
@@ -0,0 +1,154 @@
+//! Measures the allocations and latency of resolving transient dependencies.
+//!
+//! The `native` builder relies on the `impl Future` returned by [`DIBuilder::build`](DIBuilder::build), while the
+//! `boxed` one emulates the boxed future which `#[async_trait]` used to generate for every build.
+//!
+//! Run it with `cargo bench -p yadir --bench transient_resolve`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::future::Future;
+use std::hint::black_box;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use futures::executor::block_on;
+use yadir::core::contracts::DIBuilder;
+use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+use yadir::{deps, let_deps};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const ITERATIONS: usize = 100_000;
+
+#[derive(Clone)]
+struct Config {
+    retries: u32,
+}
+
+impl DIBuilder for Config {
+    type Input = deps!();
+    type Output = Config;
+
+    async fn build(_: Self::Input) -> Self::Output {
+        Config { retries: 3 }
+    }
+}
+
+#[derive(Clone)]
+struct NativeClient {
+    retries: u32,
+}
+
+impl DIBuilder for NativeClient {
+    type Input = deps!(Config);
+    type Output = NativeClient;
+
+    async fn build(input: Self::Input) -> Self::Output {
+        let_deps!(config <- input);
+
+        NativeClient {
+            retries: config.retries,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct BoxedClient {
+    retries: u32,
+}
+
+impl DIBuilder for BoxedClient {
+    type Input = deps!(Config);
+    type Output = BoxedClient;
+
+    #[allow(clippy::manual_async_fn)]
+    fn build(input: Self::Input) -> impl Future<Output = Self::Output> + Send {
+        let future: Pin<Box<dyn Future<Output = Self::Output> + Send>> = Box::pin(async move {
+            let_deps!(config <- input);
+
+            BoxedClient {
+                retries: config.retries,
+            }
+        });
+
+        future
+    }
+}
+
+fn measure<T>(manager: &mut DIManager) -> (f64, Duration)
+where
+    T: DIBuilder + 'static,
+{
+    // warm up, so that the registration itself is not accounted for
+    for _ in 0..1_000 {
+        black_box(block_on(manager.resolve::<T>()));
+    }
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+
+    for _ in 0..ITERATIONS {
+        black_box(block_on(manager.resolve::<T>()));
+    }
+
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+
+    (
+        allocations as f64 / ITERATIONS as f64,
+        elapsed / ITERATIONS as u32,
+    )
+}
+
+fn main() {
+    let mut manager = DIManager::default();
+
+    block_on(async {
+        manager
+            .register::<Config>(Some(Lifetime::Singleton))
+            .await
+            .register::<NativeClient>(Some(Lifetime::Transient))
+            .await
+            .register::<BoxedClient>(Some(Lifetime::Transient))
+            .await;
+    });
+
+    let (native_allocations, native_latency) = measure::<NativeClient>(&mut manager);
+    let (boxed_allocations, boxed_latency) = measure::<BoxedClient>(&mut manager);
+
+    let native: DIObj<NativeClient> = block_on(manager.resolve::<NativeClient>()).unwrap();
+    let boxed: DIObj<BoxedClient> = block_on(manager.resolve::<BoxedClient>()).unwrap();
+    assert_eq!(native.extract().retries, boxed.extract().retries);
+
+    println!("transient resolve ({} iterations)", ITERATIONS);
+    println!(
+        "  native impl Future:     {:>6.2} allocations, {:>8.0?} per resolve",
+        native_allocations, native_latency
+    );
+    println!(
+        "  boxed (async_trait):    {:>6.2} allocations, {:>8.0?} per resolve",
+        boxed_allocations, boxed_latency
+    );
+}
@@ -0,0 +1,225 @@
+//! Compile-time checked container builder, keeping track of the registered builders and their output types at the type level.
+//!
+//! Every registration via [`ContainerBuilder`](ContainerBuilder) prepends the builder paired with its output type to a type-level
+//! list (built out of [`HCons`](HCons) and [`HNil`](HNil)), and only compiles if all the dependencies listed by the `Input` of the
+//! builder are already in that list. Hence, a [`Container`](Container) only resolves builders which have been registered, and
+//! their dependencies cannot be missing at runtime.
+//!
+//! The index parameters of the registration and resolution methods are inferred by the compiler and are meant to be left as `_`,
+//! e.g. `builder.register::<Foo, _>(None)`.
+
+use crate::core::contracts::{DIBuilder, SyncDIBuilder};
+use crate::core::primitives::{DIManager, DIObj, Lifetime};
+use std::marker::PhantomData;
+
+/// The empty type-level list of registrations.
+pub struct HNil;
+
+/// A type-level list of registrations, made of the `(builder, output type)` pair `H` registered last and the ones registered before it.
+pub struct HCons<H, T>(PhantomData<fn() -> (H, T)>);
+
+/// The index of a registration found at the head of a type-level list.
+pub struct Here;
+
+/// The index of a registration found in the tail of a type-level list, at index `I`.
+pub struct There<I>(PhantomData<fn() -> I>);
+
+/// A trait used to check that an output type `T` is part of a type-level list, found at the index `I`.
+pub trait Contains<T, I> {}
+
+impl<B, T, Tail> Contains<T, Here> for HCons<(B, T), Tail> {}
+
+impl<T, H, Tail, I> Contains<T, There<I>> for HCons<H, Tail> where Tail: Contains<T, I> {}
+
+/// A trait used to check that a builder `B` is part of a type-level list, found at the index `I`.
+pub trait Registers<B, I> {}
+
+impl<B, T, Tail> Registers<B, Here> for HCons<(B, T), Tail> {}
+
+impl<B, H, Tail, I> Registers<B, There<I>> for HCons<H, Tail> where Tail: Registers<B, I> {}
+
+/// A trait used to check that all the dependencies listed by an input, e.g. `deps!(A, B)`, are part of the type-level list `L`.
+///
+/// It mirrors the [`GetInput`](crate::core::contracts::GetInput) implementations, i.e. it is implemented for `()`,
+/// [`DIObj<T>`](DIObj) and both flat tuples and nested pairs of them.
+pub trait Satisfies<L, I> {}
+
+impl<L> Satisfies<L, ()> for () {}
+
+impl<L, T, I> Satisfies<L, I> for DIObj<T> where L: Contains<T, I> {}
+
+macro_rules! impl_satisfies_for_tuples {
+    ($( ($( $t:ident $i:ident ),+) ),+ $(,)?) => {
+        $(
+            impl<L, $( $t, $i ),+> Satisfies<L, ($( $i, )+)> for ($( $t, )+)
+            where
+                $( $t: Satisfies<L, $i>, )+
+            {
+            }
+        )+
+    };
+}
+
+impl_satisfies_for_tuples!(
+    (A IA),
+    (A IA, B IB),
+    (A IA, B IB, C IC),
+    (A IA, B IB, C IC, D ID),
+    (A IA, B IB, C IC, D ID, E IE),
+    (A IA, B IB, C IC, D ID, E IE, F IF),
+    (A IA, B IB, C IC, D ID, E IE, F IF, G IG),
+    (A IA, B IB, C IC, D ID, E IE, F IF, G IG, H IH),
+    (A IA, B IB, C IC, D ID, E IE, F IF, G IG, H IH, I II),
+    (A IA, B IB, C IC, D ID, E IE, F IF, G IG, H IH, I II, J IJ),
+    (A IA, B IB, C IC, D ID, E IE, F IF, G IG, H IH, I II, J IJ, K IK),
+    (A IA, B IB, C IC, D ID, E IE, F IF, G IG, H IH, I II, J IJ, K IK, M IM),
+    (A IA, B IB, C IC, D ID, E IE, F IF, G IG, H IH, I II, J IJ, K IK, M IM, N IN),
+    (A IA, B IB, C IC, D ID, E IE, F IF, G IG, H IH, I II, J IJ, K IK, M IM, N IN, O IO),
+    (A IA, B IB, C IC, D ID, E IE, F IF, G IG, H IH, I II, J IJ, K IK, M IM, N IN, O IO, P IP),
+    (A IA, B IB, C IC, D ID, E IE, F IF, G IG, H IH, I II, J IJ, K IK, M IM, N IN, O IO, P IP, Q IQ),
+);
+
+/// A struct used to model a dependency injection manager under construction, whose registrations are tracked by `L`.
+///
+/// # Examples
+///
+/// ```
+/// use yadir::core::checked::ContainerBuilder;
+/// use yadir::core::primitives::Lifetime;
+/// use yadir_derive::DIBuilder;
+///
+/// #[derive(Clone, DIBuilder)]
+/// struct Bar;
+///
+/// #[derive(Clone, DIBuilder)]
+/// struct Foo(#[deps] Bar);
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let mut container = ContainerBuilder::new()
+///         .register::<Bar, _>(Some(Lifetime::Singleton))
+///         .await
+///         .register::<Foo, _>(None)
+///         .await
+///         .build();
+///
+///     assert!(container.resolve::<Foo, _>().await.is_some());
+/// }
+/// ```
+///
+/// Registering `Foo` before `Bar`, or resolving a type which has not been registered, does not compile:
+///
+/// ```compile_fail
+/// use yadir::core::checked::ContainerBuilder;
+/// use yadir_derive::DIBuilder;
+///
+/// #[derive(Clone, DIBuilder)]
+/// struct Bar;
+///
+/// #[derive(Clone, DIBuilder)]
+/// struct Foo(#[deps] Bar);
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let container = ContainerBuilder::new().register::<Foo, _>(None).await;
+/// }
+/// ```
+pub struct ContainerBuilder<L = HNil> {
+    manager: DIManager,
+    registered: PhantomData<fn() -> L>,
+}
+
+impl ContainerBuilder {
+    /// Creates a container builder without any registration.
+    pub fn new() -> Self {
+        Self {
+            manager: DIManager::default(),
+            registered: PhantomData,
+        }
+    }
+}
+
+impl Default for ContainerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L> ContainerBuilder<L> {
+    /// Registers a dependency like [`DIManager::register`](DIManager::register), provided that all its input dependencies have been registered.
+    pub async fn register<T, I>(
+        mut self,
+        lifetime: Option<Lifetime>,
+    ) -> ContainerBuilder<HCons<(T, T::Output), L>>
+    where
+        T: DIBuilder + 'static,
+        T::Input: Satisfies<L, I>,
+    {
+        self.manager.register::<T>(lifetime).await;
+
+        ContainerBuilder {
+            manager: self.manager,
+            registered: PhantomData,
+        }
+    }
+
+    /// Registers a dependency like [`DIManager::register_sync`](DIManager::register_sync), provided that all its input dependencies have been registered.
+    pub fn register_sync<T, I>(
+        mut self,
+        lifetime: Option<Lifetime>,
+    ) -> ContainerBuilder<HCons<(T, T::Output), L>>
+    where
+        T: SyncDIBuilder + 'static,
+        T::Input: Satisfies<L, I>,
+    {
+        self.manager.register_sync::<T>(lifetime);
+
+        ContainerBuilder {
+            manager: self.manager,
+            registered: PhantomData,
+        }
+    }
+
+    /// Finishes the registrations, returning the container to resolve the registered dependencies from.
+    pub fn build(self) -> Container<L> {
+        Container {
+            manager: self.manager,
+            registered: PhantomData,
+        }
+    }
+}
+
+/// A struct used to model a dependency injection manager whose registrations are tracked by `L`, see [`ContainerBuilder`](ContainerBuilder).
+pub struct Container<L> {
+    manager: DIManager,
+    registered: PhantomData<fn() -> L>,
+}
+
+impl<L> Container<L> {
+    /// Resolves a dependency like [`DIManager::resolve`](DIManager::resolve), provided that its builder has been registered.
+    ///
+    /// Like its unchecked counterpart, the method returns `None` instead of panicking if the dependency fails to build.
+    pub async fn resolve<T, I>(&mut self) -> Option<DIObj<T::Output>>
+    where
+        T: DIBuilder + 'static,
+        L: Registers<T, I>,
+    {
+        self.manager.resolve::<T>().await
+    }
+
+    /// Resolves a dependency like [`DIManager::resolve_sync`](DIManager::resolve_sync), provided that its builder has been registered.
+    ///
+    /// Like its unchecked counterpart, the method returns `None` instead of panicking if the dependency fails to build.
+    pub fn resolve_sync<T, I>(&mut self) -> Option<DIObj<T::Output>>
+    where
+        T: SyncDIBuilder + 'static,
+        L: Registers<T, I>,
+    {
+        self.manager.resolve_sync::<T>()
+    }
+
+    /// Returns the underlying dependency injection manager, giving up on the compile-time checks.
+    pub fn into_manager(self) -> DIManager {
+        self.manager
+    }
+}
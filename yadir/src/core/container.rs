@@ -0,0 +1,129 @@
+//! Static containers generated by the `container!` macro, enabled by the `derive` feature, resolving dependencies without any type map lookup.
+//!
+//! A generated container stores each singleton in a dedicated field and implements [`Provides`](Provides) for the output type of
+//! each registered builder, so that the inputs of the builders are wired via [`ResolveInput`](ResolveInput) at compile time.
+//! Hence, a missing registration is reported by the compiler instead of at runtime, as is a dependency cycle, since the container
+//! also implements [`Acyclic`](Acyclic) for each registered builder, which overflows while evaluating a builder depending on itself.
+
+use crate::core::primitives::DIObj;
+use std::future::Future;
+use std::pin::Pin;
+
+/// The future resolving an input from a static container.
+///
+/// The future is boxed, as proving that a generated accessor is `Send` would otherwise require the compiler to prove it for
+/// every lifetime of the resolved types, e.g. `Arc<dyn Printer>`, which it fails to do for nested `impl Future` types.
+pub type ResolveFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A trait implemented by a static container for each output type it can resolve.
+pub trait Provides<O> {
+    /// Resolves the dependency registered under the output type `O`, honouring the lifetime it was registered with.
+    fn provide(&mut self) -> impl Future<Output = DIObj<O>> + Send;
+}
+
+/// A trait used to resolve the input of a builder, i.e. all its dependencies, from a static container `C`.
+///
+/// It mirrors the [`GetInput`](crate::core::contracts::GetInput) implementations, i.e. it is implemented for `()`,
+/// [`DIObj<T>`](DIObj) and both flat tuples and nested pairs of them.
+pub trait ResolveInput<C>: Sized {
+    /// Resolves all the dependencies listed by the input from the container, in declaration order.
+    fn resolve_input(container: &mut C) -> ResolveFuture<'_, Self>;
+}
+
+impl<C> ResolveInput<C> for () {
+    fn resolve_input(_: &mut C) -> ResolveFuture<'_, Self> {
+        Box::pin(async {})
+    }
+}
+
+impl<C, T> ResolveInput<C> for DIObj<T>
+where
+    C: Provides<T>,
+    T: 'static,
+{
+    fn resolve_input(container: &mut C) -> ResolveFuture<'_, Self> {
+        Box::pin(container.provide())
+    }
+}
+
+macro_rules! impl_resolve_input_for_tuples {
+    ($( ($( $t:ident ),+) ),+ $(,)?) => {
+        $(
+            impl<Container, $( $t ),+> ResolveInput<Container> for ($( $t, )+)
+            where
+                Container: Send,
+                $( $t: ResolveInput<Container> + Send + 'static, )+
+            {
+                fn resolve_input(container: &mut Container) -> ResolveFuture<'_, Self> {
+                    Box::pin(async move { ($( $t::resolve_input(container).await, )+) })
+                }
+            }
+        )+
+    };
+}
+
+impl_resolve_input_for_tuples!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H),
+    (A, B, C, D, E, F, G, H, I),
+    (A, B, C, D, E, F, G, H, I, J),
+    (A, B, C, D, E, F, G, H, I, J, K),
+    (A, B, C, D, E, F, G, H, I, J, K, L),
+    (A, B, C, D, E, F, G, H, I, J, K, L, M),
+    (A, B, C, D, E, F, G, H, I, J, K, L, M, N),
+    (A, B, C, D, E, F, G, H, I, J, K, L, M, N, O),
+    (A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P),
+);
+
+/// A trait implemented by the output type of each builder registered in a static container `C`, provided that the builder does not
+/// depend on itself.
+///
+/// It is implemented as long as the input of the builder implements [`AcyclicInput`](AcyclicInput), which requires the outputs of
+/// all its dependencies to implement it in turn, hence the compiler overflows while evaluating the requirement for a dependency cycle.
+pub trait Acyclic<C> {}
+
+/// A trait used to check that all the dependencies listed by an input are [`Acyclic`](Acyclic) in a static container `C`.
+///
+/// It mirrors the [`ResolveInput`](ResolveInput) implementations.
+pub trait AcyclicInput<C> {}
+
+impl<C> AcyclicInput<C> for () {}
+
+impl<C, T> AcyclicInput<C> for DIObj<T> where T: Acyclic<C> {}
+
+macro_rules! impl_acyclic_input_for_tuples {
+    ($( ($( $t:ident ),+) ),+ $(,)?) => {
+        $(
+            impl<Container, $( $t ),+> AcyclicInput<Container> for ($( $t, )+)
+            where
+                $( $t: AcyclicInput<Container>, )+
+            {
+            }
+        )+
+    };
+}
+
+impl_acyclic_input_for_tuples!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H),
+    (A, B, C, D, E, F, G, H, I),
+    (A, B, C, D, E, F, G, H, I, J),
+    (A, B, C, D, E, F, G, H, I, J, K),
+    (A, B, C, D, E, F, G, H, I, J, K, L),
+    (A, B, C, D, E, F, G, H, I, J, K, L, M),
+    (A, B, C, D, E, F, G, H, I, J, K, L, M, N),
+    (A, B, C, D, E, F, G, H, I, J, K, L, M, N, O),
+    (A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P),
+);
@@ -1,12 +1,18 @@
-use crate::core::primitives::DIManager;
-use async_trait::async_trait;
+use crate::core::introspection::{BuilderMetadata, TypeDescriptor};
+use crate::core::primitives::{DIManager, Lifetime, ResolveError};
+use std::any::type_name;
+use std::future::{ready, Future};
+use std::marker::PhantomData;
+use std::time::Duration;
 
 /// A trait for building dependencies.
 ///
 /// The `DIBuilder` trait is used to define a dependency that can be built by the dependency injection manager.
 /// For each dependency, you need to implement the [`build`](DIBuilder::build) method that takes
 /// as input the [`Self::Input`](DIBuilder::Input) associated type and returns the [`Self::Output`](DIBuilder::Output) associated type.
-#[async_trait]
+///
+/// The [`build`](DIBuilder::build) method relies on native `async fn` in traits, hence it can be implemented as a plain `async fn`
+/// without boxing the returned future, as long as the future is `Send`.
 pub trait DIBuilder {
     /// The input type embedding all the dependencies needed to build the current dependency.
     ///
@@ -21,8 +27,6 @@ pub trait DIBuilder {
     /// # Examples
     ///
     /// ```
-    /// use async_trait::async_trait;
-    /// use yadir::{deps, let_deps};
     /// use yadir::core::contracts::{DIBuilder, GetInput};
     /// use yadir::core::primitives::{DIManager, DIObj};
     /// use yadir_derive::DIBuilder;
@@ -50,7 +54,11 @@ pub trait DIBuilder {
     /// The output type is the type of the dependency that will be built by the builder after resolving all its dependencies.
     /// Notice that the lifetime of the output type must be `'static` to ensure that the dependency injection manager does not
     /// allow for invalid references to types to be stored in the type map.
-    type Output: 'static + Clone;
+    ///
+    /// The output type does not need to be `Clone`, as the dependency injection manager stores it behind a shared
+    /// [`DIObj`](super::primitives::DIObj) handle, hence connection pools, channels or receivers can be registered as is.
+    /// It must be `Send` and `Sync` though, as the handle can be shared across threads.
+    type Output: Send + Sync + 'static;
 
     /// Builds the dependency using the input type.
     ///
@@ -60,17 +68,34 @@ pub trait DIBuilder {
     /// # Examples
     ///
     /// ```
-    /// use async_trait::async_trait;
     /// use yadir::{deps, let_deps};
     /// use yadir::core::contracts::{DIBuilder, GetInput};
     /// use yadir::core::primitives::{DIManager, DIObj};
-    /// use yadir_derive::DIBuilder;
     ///
-    /// #[derive(Clone, DIBuilder)]
+    /// #[derive(Clone)]
     /// struct Bar;
     ///
-    /// #[derive(Clone, DIBuilder)]
-    /// struct Foo(#[deps] Bar);
+    /// impl DIBuilder for Bar {
+    ///     type Input = deps!();
+    ///     type Output = Self;
+    ///
+    ///     async fn build(_: Self::Input) -> Self::Output {
+    ///         Self
+    ///     }
+    /// }
+    ///
+    /// #[derive(Clone)]
+    /// struct Foo(Bar);
+    ///
+    /// impl DIBuilder for Foo {
+    ///     type Input = deps!(Bar);
+    ///     type Output = Self;
+    ///
+    ///     async fn build(input: Self::Input) -> Self::Output {
+    ///         let_deps!(bar <- input);
+    ///         Self(bar)
+    ///     }
+    /// }
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -79,10 +104,287 @@ pub trait DIBuilder {
     ///    manager.build::<Bar>().await;
     ///    manager.build::<Foo>().await;
     ///
-    ///    assert!(manager.has::<DIObj<Bar>>());
+    ///    assert!(manager.has::<DIObj<Foo>>());
     /// }
     /// ```
-    async fn build(input: Self::Input) -> Self::Output;
+    fn build(input: Self::Input) -> impl Future<Output = Self::Output> + Send;
+
+    /// Builds the dependency using the input type, failing with [`ResolveError::Failed`](ResolveError::Failed) if it cannot be built.
+    ///
+    /// The dependency injection manager always builds dependencies via this method, which never fails by default. Fallible builders,
+    /// such as `#[injectable]` constructors returning a `Result`, override it, while their [`build`](DIBuilder::build) method panics
+    /// on failure, as it cannot report it otherwise.
+    fn try_build(
+        input: Self::Input,
+    ) -> impl Future<Output = Result<Self::Output, ResolveError>> + Send {
+        let build = Self::build(input);
+        async move { Ok(build.await) }
+    }
+
+    /// Describes the builder, its output and its dependencies without building anything.
+    ///
+    /// The default implementation names the builder and its output via [`std::any::type_name`] and collects the dependencies
+    /// from [`Self::Input`](DIBuilder::Input), while `#[derive(DIBuilder)]` keeps the type names as written in the struct definition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::contracts::DIBuilder;
+    /// use yadir::core::primitives::DIObj;
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Bar;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Foo(#[deps] Bar);
+    ///
+    /// let metadata = Foo::metadata();
+    ///
+    /// assert_eq!(metadata.type_name, "Foo");
+    /// assert_eq!(metadata.output.name, "Foo");
+    /// assert_eq!(metadata.dependencies[0].name, "Bar");
+    /// ```
+    fn metadata() -> BuilderMetadata
+    where
+        Self: Sized + 'static,
+    {
+        BuilderMetadata {
+            type_name: type_name::<Self>(),
+            output: TypeDescriptor::of::<Self::Output>(),
+            dependencies: Self::Input::dependencies(),
+        }
+    }
+
+    /// Registers the additional outputs of the builder along with its main output, see [`DIBuilderAs`](DIBuilderAs).
+    ///
+    /// The method is invoked by [`register`](DIManager::register) with the same lifetime as the main output and does nothing by default,
+    /// while `#[derive(DIBuilder)]` registers all the outputs listed in `#[build_as(...)]` besides the first one via the hidden
+    /// `DIManager::register_alias`, which behaves like [`register_as`](DIManager::register_as) behind a boxed future.
+    fn register_aliases(
+        manager: &mut DIManager,
+        lifetime: Option<Lifetime>,
+    ) -> impl Future<Output = ()> + Send
+    where
+        Self: Sized + 'static,
+    {
+        let _ = (manager, lifetime);
+        ready(())
+    }
+}
+
+/// A trait for building a dependency as an additional output type.
+///
+/// The `DIBuilderAs` trait allows a single implementation to be registered under several output types, e.g. under several
+/// trait objects it implements. Each output type is registered and resolved separately via [`register_as`](DIManager::register_as)
+/// and [`resolve_as`](DIManager::resolve_as), reusing the [`Input`](DIBuilder::Input) of the builder.
+///
+/// `#[derive(DIBuilder)]` implements the trait for every output listed in `#[build_as(...)]`.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use yadir::core::contracts::{DIBuilder, DIBuilderAs};
+/// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+/// use yadir_derive::DIBuilder;
+///
+/// trait Printer: Send + Sync {
+///     fn print(&self) -> String;
+/// }
+///
+/// trait Writer: Send + Sync {
+///     fn write(&self) -> String;
+/// }
+///
+/// #[derive(DIBuilder)]
+/// #[build_as(Arc<dyn Printer>, Arc<dyn Writer>)]
+/// struct Console;
+///
+/// impl Printer for Console {
+///     fn print(&self) -> String {
+///         "print".to_string()
+///     }
+/// }
+///
+/// impl Writer for Console {
+///     fn write(&self) -> String {
+///         "write".to_string()
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let mut manager = DIManager::default();
+///     manager.register::<Console>(Some(Lifetime::Singleton)).await;
+///
+///     let printer = manager.resolve::<Console>().await.unwrap();
+///     let writer = manager.resolve_as::<Console, Arc<dyn Writer>>().await.unwrap();
+///
+///     assert_eq!(printer.read().unwrap().print(), "print");
+///     assert_eq!(writer.read().unwrap().write(), "write");
+/// }
+/// ```
+pub trait DIBuilderAs<O>: DIBuilder
+where
+    O: Send + Sync + 'static,
+{
+    /// Builds the dependency as the given output type using the input type.
+    fn build_as(input: Self::Input) -> impl Future<Output = O> + Send;
+
+    /// Describes the builder, the given output type and its dependencies without building anything, see [`DIBuilder::metadata`](DIBuilder::metadata).
+    fn metadata_as() -> BuilderMetadata
+    where
+        Self: Sized + 'static,
+    {
+        BuilderMetadata {
+            output: TypeDescriptor::of::<O>(),
+            ..<Self as DIBuilder>::metadata()
+        }
+    }
+}
+
+/// Adapts a [`DIBuilderAs`](DIBuilderAs) implementation to a [`DIBuilder`](DIBuilder) building the given output type,
+/// so that additional outputs are registered and resolved like any other dependency.
+pub(crate) struct Alias<T, O>(PhantomData<fn() -> (T, O)>);
+
+impl<T, O> DIBuilder for Alias<T, O>
+where
+    T: DIBuilderAs<O> + 'static,
+    O: Send + Sync + 'static,
+{
+    type Input = T::Input;
+    type Output = O;
+
+    fn build(input: Self::Input) -> impl Future<Output = Self::Output> + Send {
+        T::build_as(input)
+    }
+
+    fn metadata() -> BuilderMetadata
+    where
+        Self: Sized + 'static,
+    {
+        T::metadata_as()
+    }
+}
+
+/// A trait for building dependencies synchronously.
+///
+/// The `SyncDIBuilder` trait is the blocking counterpart of [`DIBuilder`](DIBuilder), meant for dependencies that can be built
+/// without any async runtime. Such dependencies can be registered and resolved via the blocking API of the dependency injection manager,
+/// e.g. [`register_sync`](DIManager::register_sync) and [`resolve_sync`](DIManager::resolve_sync).
+///
+/// Every `SyncDIBuilder` is also a [`DIBuilder`](DIBuilder), so synchronous and asynchronous builders can be freely mixed:
+/// synchronous builders can depend on dependencies registered asynchronously and the other way around.
+///
+/// # Examples
+///
+/// ```
+/// use yadir::deps;
+/// use yadir::core::contracts::SyncDIBuilder;
+/// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+///
+/// #[derive(Clone)]
+/// struct Config(u16);
+///
+/// impl SyncDIBuilder for Config {
+///     type Input = deps!();
+///     type Output = Self;
+///
+///     fn build(_: Self::Input) -> Self::Output {
+///         Self(8080)
+///     }
+/// }
+///
+/// let mut manager = DIManager::default();
+/// manager.register_sync::<Config>(Some(Lifetime::Singleton));
+///
+/// assert_eq!(manager.resolve_sync::<Config>().unwrap().extract().0, 8080);
+/// ```
+pub trait SyncDIBuilder {
+    /// The input type embedding all the dependencies needed to build the current dependency, see [`DIBuilder::Input`](DIBuilder::Input).
+    type Input: GetInput + Clone;
+
+    /// The output type representing the built dependency, see [`DIBuilder::Output`](DIBuilder::Output).
+    type Output: Send + Sync + 'static;
+
+    /// Builds the dependency using the input type, without blocking on any async runtime.
+    fn build(input: Self::Input) -> Self::Output;
+
+    /// Builds the dependency using the input type, failing if it cannot be built, see [`DIBuilder::try_build`](DIBuilder::try_build).
+    fn try_build(input: Self::Input) -> Result<Self::Output, ResolveError> {
+        Ok(Self::build(input))
+    }
+
+    /// Describes the builder, its output and its dependencies without building anything, see [`DIBuilder::metadata`](DIBuilder::metadata).
+    fn metadata() -> BuilderMetadata
+    where
+        Self: Sized + 'static,
+    {
+        BuilderMetadata {
+            type_name: type_name::<Self>(),
+            output: TypeDescriptor::of::<Self::Output>(),
+            dependencies: Self::Input::dependencies(),
+        }
+    }
+}
+
+impl<T: SyncDIBuilder> DIBuilder for T {
+    type Input = <T as SyncDIBuilder>::Input;
+    type Output = <T as SyncDIBuilder>::Output;
+
+    fn build(input: Self::Input) -> impl Future<Output = Self::Output> + Send {
+        ready(<T as SyncDIBuilder>::build(input))
+    }
+
+    fn try_build(
+        input: Self::Input,
+    ) -> impl Future<Output = Result<Self::Output, ResolveError>> + Send {
+        ready(<T as SyncDIBuilder>::try_build(input))
+    }
+
+    fn metadata() -> BuilderMetadata
+    where
+        Self: Sized + 'static,
+    {
+        <T as SyncDIBuilder>::metadata()
+    }
+}
+
+/// A trait used to model a provider, i.e. a function building a dependency turned into a builder type via `#[provider]`.
+///
+/// Providers build the types which cannot derive [`DIBuilder`](DIBuilder) themselves, such as the ones defined by other crates.
+/// They are registered with their own lifetime via [`register_provider`](DIManager::register_provider).
+///
+/// # Examples
+///
+/// ```
+/// use yadir_derive::provider;
+/// use yadir::core::primitives::{DIManager, Lifetime};
+///
+/// pub struct Client {
+///     url: String,
+/// }
+///
+/// #[provider(lifetime = "singleton")]
+/// async fn make_client() -> Client {
+///     Client { url: "http://localhost".to_string() }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let mut manager = DIManager::default();
+///     manager.register_provider(make_client).await;
+///
+///     let client = manager.resolve::<make_client>().await.unwrap();
+///
+///     assert_eq!(client.read().unwrap().url, "http://localhost");
+///     assert_eq!(manager.registrations()[0].lifetime, Lifetime::Singleton);
+/// }
+/// ```
+pub trait Provider: DIBuilder {
+    /// The lifetime the provider is registered with, or `None` to register it as transient.
+    const LIFETIME: Option<Lifetime>;
 }
 
 /// A trait used to retrieve dependencies from the dependency injection manager.
@@ -91,7 +393,94 @@ pub trait DIBuilder {
 /// It is implemented for the following cases:
 /// - [`DIObj<T>`](super::primitives::DIObj): to retrieve a dependency wrapped in a thread-safe reference counted mutex from the dependency injection manager (**base case**).
 /// - `()`: to return the unit type when no dependencies are needed (**base case**).
-/// - `(S, T)`: to retrieve multiple dependencies by recursively resolving each dependency (**inductive case**).
+/// - `(A, B, ...)`: to retrieve multiple dependencies by resolving each element in order, for tuples of up to 16 elements (**inductive case**).
+///   Dependencies are usually given as flat tuples via [`deps!`](crate::deps), while nested pairs keep working as well.
 pub trait GetInput: Sized {
     fn get_input(manager: &DIManager) -> Option<Self>;
+
+    /// Describes the output types of all the dependencies embedded in the implementer type.
+    fn dependencies() -> Vec<TypeDescriptor>;
+}
+
+/// A trait for plugging cross-cutting behavior into the dependency injection manager.
+///
+/// The `Interceptor` trait is used to observe (and possibly veto) the dependencies being built and resolved by a
+/// [`DIManager`](DIManager), e.g. to log each build, measure build durations or count resolves. All the callbacks
+/// are no-ops by default, so implementers only need to override the ones they are interested in.
+///
+/// The callbacks are invoked from [`build`](DIManager::build), [`register`](DIManager::register) and
+/// [`resolve`](DIManager::resolve) in a well-defined order when multiple interceptors are installed:
+/// - [`before_build`](Interceptor::before_build) is invoked in installation order, stopping at the first interceptor vetoing the build.
+/// - [`after_build`](Interceptor::after_build) is invoked in reverse installation order, so that the first installed interceptor wraps all the others.
+/// - [`on_resolve`](Interceptor::on_resolve) and [`on_resolve_error`](Interceptor::on_resolve_error) are invoked in installation order.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use yadir::core::contracts::{DIBuilder, Interceptor};
+/// use yadir::core::introspection::BuilderMetadata;
+/// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+/// use yadir_derive::DIBuilder;
+///
+/// #[derive(Clone, DIBuilder)]
+/// struct Bar;
+///
+/// #[derive(Default)]
+/// struct ResolveCounter(AtomicUsize);
+///
+/// impl Interceptor for ResolveCounter {
+///     fn on_resolve(&self, _: &BuilderMetadata, _: Lifetime) {
+///         self.0.fetch_add(1, Ordering::SeqCst);
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let counter = Arc::new(ResolveCounter::default());
+///
+///     let mut manager = DIManager::default();
+///     manager
+///         .add_interceptor(counter.clone())
+///         .register::<Bar>(Some(Lifetime::Singleton)).await;
+///
+///     manager.resolve::<Bar>().await;
+///     manager.resolve::<Bar>().await;
+///
+///     assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+/// }
+/// ```
+pub trait Interceptor: Send + Sync {
+    /// Invoked before a dependency is built. Returning `false` vetoes the build, which then fails with [`ResolveError::Vetoed`](ResolveError::Vetoed).
+    fn before_build(&self, _metadata: &BuilderMetadata, _lifetime: Lifetime) -> bool {
+        true
+    }
+
+    /// Invoked after a dependency has been built, along with the time spent building it.
+    fn after_build(&self, _metadata: &BuilderMetadata, _lifetime: Lifetime, _elapsed: Duration) {}
+
+    /// Invoked after a dependency has been successfully resolved, whether it has been built or retrieved as a singleton.
+    fn on_resolve(&self, _metadata: &BuilderMetadata, _lifetime: Lifetime) {}
+
+    /// Invoked when a dependency could not be built or resolved.
+    fn on_resolve_error(&self, _metadata: &BuilderMetadata, _error: &ResolveError) {}
+}
+
+impl<I: Interceptor + ?Sized> Interceptor for std::sync::Arc<I> {
+    fn before_build(&self, metadata: &BuilderMetadata, lifetime: Lifetime) -> bool {
+        (**self).before_build(metadata, lifetime)
+    }
+
+    fn after_build(&self, metadata: &BuilderMetadata, lifetime: Lifetime, elapsed: Duration) {
+        (**self).after_build(metadata, lifetime, elapsed)
+    }
+
+    fn on_resolve(&self, metadata: &BuilderMetadata, lifetime: Lifetime) {
+        (**self).on_resolve(metadata, lifetime)
+    }
+
+    fn on_resolve_error(&self, metadata: &BuilderMetadata, error: &ResolveError) {
+        (**self).on_resolve_error(metadata, error)
+    }
 }
@@ -0,0 +1,321 @@
+use crate::core::primitives::Lifetime;
+use std::any::{type_name, TypeId};
+use std::fmt::Write;
+
+/// A simple descriptor of a type taking part in the dependency graph.
+///
+/// The `TypeDescriptor` struct pairs the [`TypeId`](TypeId) of a type, used to match dependencies against registrations,
+/// with a human-readable name, used when rendering the dependency graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeDescriptor {
+    pub id: TypeId,
+    pub name: &'static str,
+}
+
+impl TypeDescriptor {
+    /// Describes a type using [`std::any::type_name`] as its name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::any::TypeId;
+    /// use yadir::core::introspection::TypeDescriptor;
+    ///
+    /// let descriptor = TypeDescriptor::of::<String>();
+    ///
+    /// assert_eq!(descriptor.id, TypeId::of::<String>());
+    /// assert_eq!(descriptor.name, "alloc::string::String");
+    /// ```
+    pub fn of<T>() -> Self
+    where
+        T: ?Sized + 'static,
+    {
+        Self::named::<T>(type_name::<T>())
+    }
+
+    /// Describes a type using a custom name, e.g. the type as spelled in the source code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::introspection::TypeDescriptor;
+    ///
+    /// let descriptor = TypeDescriptor::named::<String>("String");
+    ///
+    /// assert_eq!(descriptor.id, TypeDescriptor::of::<String>().id);
+    /// assert_eq!(descriptor.name, "String");
+    /// ```
+    pub fn named<T>(name: &'static str) -> Self
+    where
+        T: ?Sized + 'static,
+    {
+        Self {
+            id: TypeId::of::<T>(),
+            name,
+        }
+    }
+}
+
+/// The static metadata of a [`DIBuilder`](super::contracts::DIBuilder), available without building anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuilderMetadata {
+    /// The name of the builder type.
+    pub type_name: &'static str,
+    /// The output type produced by the builder.
+    pub output: TypeDescriptor,
+    /// The output types of all the dependencies needed by the builder.
+    pub dependencies: Vec<TypeDescriptor>,
+}
+
+/// A descriptor of a dependency registered in a [`DIManager`](super::primitives::DIManager).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistrationDescriptor {
+    /// The name of the builder type.
+    pub type_name: &'static str,
+    /// The output type under which the dependency is registered.
+    pub output: TypeDescriptor,
+    /// The key under which the dependency is stored, i.e. the [`DIObj`](super::primitives::DIObj) of its output type,
+    /// as checked by [`DIManager::has`](super::primitives::DIManager::has).
+    pub key: TypeDescriptor,
+    /// The lifetime of the dependency.
+    pub lifetime: Lifetime,
+    /// The output types of all the dependencies needed to build the dependency.
+    pub dependencies: Vec<TypeDescriptor>,
+}
+
+impl RegistrationDescriptor {
+    pub(crate) fn new(metadata: BuilderMetadata, key: TypeDescriptor, lifetime: Lifetime) -> Self {
+        Self {
+            type_name: metadata.type_name,
+            output: metadata.output,
+            key,
+            lifetime,
+            dependencies: metadata.dependencies,
+        }
+    }
+}
+
+/// A dependency graph built out of the registrations of a [`DIManager`](super::primitives::DIManager).
+///
+/// The `DependencyGraph` struct is used to export the registrations and the dependencies between them
+/// as [Graphviz DOT](https://graphviz.org/doc/info/lang.html), [Mermaid](https://mermaid.js.org/syntax/flowchart.html) or JSON.
+/// Each node is a registration and each edge goes from a dependent registration to one of its dependencies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyGraph(Vec<RegistrationDescriptor>);
+
+impl DependencyGraph {
+    pub(crate) fn new(registrations: Vec<RegistrationDescriptor>) -> Self {
+        Self(registrations)
+    }
+
+    /// Returns the registrations embedded in the graph, in registration order.
+    pub fn registrations(&self) -> &[RegistrationDescriptor] {
+        &self.0
+    }
+
+    /// Renders the graph in the Graphviz DOT language.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Bar;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Foo(#[deps] Bar);
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///     manager
+    ///         .register::<Bar>(Some(Lifetime::Singleton)).await
+    ///         .register::<Foo>(None).await;
+    ///
+    ///     assert_eq!(
+    ///         manager.dependency_graph().to_dot(),
+    ///         "digraph dependencies {\n    \
+    ///             n0 [label=\"Bar\\n(singleton)\"];\n    \
+    ///             n1 [label=\"Foo\\n(transient)\"];\n    \
+    ///             n1 -> n0;\n\
+    ///         }\n"
+    ///     );
+    /// }
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph dependencies {\n");
+
+        for (index, registration) in self.0.iter().enumerate() {
+            let _ = writeln!(
+                dot,
+                "    n{index} [label=\"{}\\n({})\"];",
+                escape_dot(&Self::label(registration)),
+                registration.lifetime
+            );
+        }
+        for (from, to) in self.edges() {
+            let _ = writeln!(dot, "    n{from} -> n{to};");
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders the graph as a Mermaid flowchart.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Bar;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Foo(#[deps] Bar);
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///     manager
+    ///         .register::<Bar>(Some(Lifetime::Singleton)).await
+    ///         .register::<Foo>(None).await;
+    ///
+    ///     assert_eq!(
+    ///         manager.dependency_graph().to_mermaid(),
+    ///         "flowchart TD\n    \
+    ///             n0[\"Bar<br/>(singleton)\"]\n    \
+    ///             n1[\"Foo<br/>(transient)\"]\n    \
+    ///             n1 --> n0\n"
+    ///     );
+    /// }
+    /// ```
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("flowchart TD\n");
+
+        for (index, registration) in self.0.iter().enumerate() {
+            let _ = writeln!(
+                mermaid,
+                "    n{index}[\"{}<br/>({})\"]",
+                escape_mermaid(&Self::label(registration)),
+                registration.lifetime
+            );
+        }
+        for (from, to) in self.edges() {
+            let _ = writeln!(mermaid, "    n{from} --> n{to}");
+        }
+
+        mermaid
+    }
+
+    /// Renders the graph as a JSON document.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Foo;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///     manager.register::<Foo>(Some(Lifetime::Singleton)).await;
+    ///
+    ///     assert_eq!(
+    ///         manager.dependency_graph().to_json(),
+    ///         r#"{"registrations":[{"type_name":"Foo","output":"Foo","lifetime":"singleton","dependencies":[]}]}"#
+    ///     );
+    /// }
+    /// ```
+    pub fn to_json(&self) -> String {
+        let registrations = self
+            .0
+            .iter()
+            .map(|registration| {
+                let dependencies = registration
+                    .dependencies
+                    .iter()
+                    .map(|dependency| escape_json(dependency.name))
+                    .collect::<Vec<_>>()
+                    .join(",");
+
+                format!(
+                    r#"{{"type_name":{},"output":{},"lifetime":"{}","dependencies":[{}]}}"#,
+                    escape_json(registration.type_name),
+                    escape_json(registration.output.name),
+                    registration.lifetime,
+                    dependencies
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(r#"{{"registrations":[{registrations}]}}"#)
+    }
+
+    /// Labels a registration by its builder name, followed by its output type if different.
+    fn label(registration: &RegistrationDescriptor) -> String {
+        if registration.type_name == registration.output.name {
+            registration.type_name.to_string()
+        } else {
+            format!("{}: {}", registration.type_name, registration.output.name)
+        }
+    }
+
+    /// Returns the node indices of all the edges, from dependents to their dependencies.
+    fn edges(&self) -> Vec<(usize, usize)> {
+        self.0
+            .iter()
+            .enumerate()
+            .flat_map(|(from, registration)| {
+                registration
+                    .dependencies
+                    .iter()
+                    .filter_map(move |dependency| {
+                        self.0
+                            .iter()
+                            .position(|r| r.output.id == dependency.id)
+                            .map(|to| (from, to))
+                    })
+            })
+            .collect()
+    }
+}
+
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(value: &str) -> String {
+    value
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
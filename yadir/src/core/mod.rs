@@ -1,2 +1,10 @@
+pub mod checked;
+pub mod container;
 pub mod contracts;
+pub mod introspection;
 pub mod primitives;
+#[cfg(feature = "registry")]
+pub mod registry;
+#[cfg(feature = "tokio")]
+pub mod runtime;
+pub(crate) mod telemetry;
@@ -1,7 +1,24 @@
-use crate::core::contracts::{DIBuilder, GetInput};
-use std::any::{Any, TypeId};
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use crate::core::contracts::{
+    Alias, DIBuilder, DIBuilderAs, GetInput, Interceptor, Provider, SyncDIBuilder,
+};
+use crate::core::introspection::{
+    BuilderMetadata, DependencyGraph, RegistrationDescriptor, TypeDescriptor,
+};
+use crate::core::telemetry;
+use async_lock::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::any::{type_name, Any, TypeId};
+use std::collections::{hash_map, HashMap, HashSet};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::future::Future;
+use std::hash::{BuildHasherDefault, Hasher};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
 
 /// A simple enum to represent the lifetime of a dependency.
 ///
@@ -15,9 +32,139 @@ pub enum Lifetime {
     Singleton,
 }
 
-/// A simple type map that stores values by their type.
+impl Display for Lifetime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Transient => write!(f, "transient"),
+            Self::Singleton => write!(f, "singleton"),
+        }
+    }
+}
+
+/// An enum to represent the reasons why a dependency could not be built or resolved.
+///
+/// The `ResolveError` enum is reported to the installed [`Interceptor`](Interceptor)s and has four variants:
+/// - `NotRegistered`: The dependency has not been registered in the dependency injection manager.
+/// - `MissingDependencies`: Some of the input dependencies needed to build the dependency are missing.
+/// - `Vetoed`: An interceptor vetoed the build of the dependency.
+/// - `Failed`: The builder failed to build the dependency, see [`DIBuilder::try_build`](DIBuilder::try_build).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolveError {
+    NotRegistered,
+    MissingDependencies,
+    Vetoed,
+    Failed,
+}
+
+impl Display for ResolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotRegistered => write!(f, "the dependency is not registered"),
+            Self::MissingDependencies => write!(f, "some input dependencies are missing"),
+            Self::Vetoed => write!(f, "the build was vetoed by an interceptor"),
+            Self::Failed => write!(f, "the builder failed to build the dependency"),
+        }
+    }
+}
+
+impl Error for ResolveError {}
+
+/// A hasher for [`TypeId`](TypeId) keys, which are already well distributed hashes and hence used as is.
 #[derive(Default)]
-pub struct TypeMap(HashMap<TypeId, (Lifetime, Box<dyn Any>)>);
+struct TypeIdHasher(u64);
+
+impl Hasher for TypeIdHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        // `TypeId` only hashes integers, so this is never reached in practice
+        for &byte in bytes {
+            self.0 = self.0.rotate_left(8) ^ u64::from(byte);
+        }
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.0 = n;
+    }
+}
+
+/// A value stored in a [`TypeMap`](TypeMap), which can be cloned and shared across threads.
+trait AnyValue: Any + Send + Sync {
+    fn clone_boxed(&self) -> Box<dyn AnyValue>;
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<T: Clone + Send + Sync + 'static> AnyValue for T {
+    fn clone_boxed(&self) -> Box<dyn AnyValue> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// A value stored in a [`TypeMap`](TypeMap) along with its lifetime and type name.
+struct Slot {
+    type_name: &'static str,
+    lifetime: Lifetime,
+    value: Box<dyn AnyValue>,
+}
+
+impl Slot {
+    fn new<T: Clone + Send + Sync + 'static>(t: T, lifetime: Lifetime) -> Self {
+        Self {
+            type_name: type_name::<T>(),
+            lifetime,
+            value: Box::new(t),
+        }
+    }
+
+    fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        (*self.value).as_any().downcast_ref()
+    }
+
+    fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        (*self.value).as_any_mut().downcast_mut()
+    }
+
+    fn into_inner<T: 'static>(self) -> Option<T> {
+        self.value.into_any().downcast().ok().map(|t| *t)
+    }
+}
+
+impl Clone for Slot {
+    fn clone(&self) -> Self {
+        Self {
+            type_name: self.type_name,
+            lifetime: self.lifetime,
+            value: (*self.value).clone_boxed(),
+        }
+    }
+}
+
+/// A simple type map that stores values by their type.
+///
+/// The `TypeMap` holds at most one value per type, along with the [`Lifetime`](Lifetime) used by the dependency injection
+/// manager, and can also be used standalone as an extensions map. Values must be `Clone`, `Send` and `Sync`, so that the
+/// map itself can be cloned and shared across threads.
+#[derive(Default, Clone)]
+pub struct TypeMap(HashMap<TypeId, Slot, BuildHasherDefault<TypeIdHasher>>);
 
 impl TypeMap {
     /// Inserts a value into the map with its inferred type as the key.
@@ -34,12 +181,43 @@ impl TypeMap {
     /// ```
     pub fn set<T>(&mut self, t: T, lifetime: Option<Lifetime>)
     where
-        T: Any + 'static,
+        T: Clone + Send + Sync + 'static,
+    {
+        let lifetime = lifetime.unwrap_or_default();
+
+        // reuse the existing allocation when overwriting a value of the same type
+        match self.0.get_mut(&TypeId::of::<T>()) {
+            Some(slot) => {
+                slot.lifetime = lifetime;
+                if let Some(value) = slot.downcast_mut::<T>() {
+                    *value = t;
+                }
+            }
+            None => {
+                self.0.insert(TypeId::of::<T>(), Slot::new(t, lifetime));
+            }
+        }
+    }
+
+    /// Inserts a value into the map with its inferred type as the key, returning the previous value of the same type if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::primitives::TypeMap;
+    ///
+    /// let mut map = TypeMap::default();
+    ///
+    /// assert_eq!(map.insert(42), None);
+    /// assert_eq!(map.insert(43), Some(42));
+    /// ```
+    pub fn insert<T>(&mut self, t: T) -> Option<T>
+    where
+        T: Clone + Send + Sync + 'static,
     {
-        self.0.insert(
-            TypeId::of::<T>(),
-            (lifetime.unwrap_or_default(), Box::new(t)),
-        );
+        self.0
+            .insert(TypeId::of::<T>(), Slot::new(t, Lifetime::default()))
+            .and_then(Slot::into_inner)
     }
 
     /// Retrieves a value from the map by its type. Returns `None` if the value is not found.
@@ -57,9 +235,7 @@ impl TypeMap {
     where
         T: Any + 'static,
     {
-        self.0
-            .get(&TypeId::of::<T>())
-            .map(|(_, boxed)| boxed.downcast_ref::<T>().unwrap())
+        self.0.get(&TypeId::of::<T>()).and_then(Slot::downcast_ref)
     }
 
     /// Retrieves a mutable reference to a value from the map by its type. Returns `None` if the value is not found.
@@ -85,7 +261,7 @@ impl TypeMap {
     {
         self.0
             .get_mut(&TypeId::of::<T>())
-            .map(|(_, boxed)| boxed.downcast_mut::<T>().unwrap())
+            .and_then(Slot::downcast_mut)
     }
 
     /// Retrieves the lifetime of a value from the map by its type. Returns `None` if the value is not found.
@@ -106,9 +282,7 @@ impl TypeMap {
     where
         T: Any + 'static,
     {
-        self.0
-            .get(&TypeId::of::<T>())
-            .map(|(lifetime, _)| *lifetime)
+        self.0.get(&TypeId::of::<T>()).map(|slot| slot.lifetime)
     }
 
     /// Checks if the map contains a value of a given type.
@@ -130,204 +304,1712 @@ impl TypeMap {
     {
         self.0.contains_key(&TypeId::of::<T>())
     }
-}
-
-/// A new type wrapper for a thread-safe reference counted mutex to handle thread-safe sharing of embedded dependencies.
-#[derive(Clone)]
-pub struct DIObj<T: Clone>(Arc<Mutex<T>>);
-
-impl<T: Clone> DIObj<T> {
-    pub fn new(t: T) -> Self {
-        Self(Arc::new(Mutex::new(t)))
-    }
-
-    pub fn extract(&self) -> T {
-        self.0.lock().unwrap().clone()
-    }
-}
-
-/// A struct used to model a dependency injection manager.
-///
-/// The `DIManager` struct is used to manage the dependencies and build them using the [`build`](DIManager::build) method.
-/// The manager uses a [`TypeMap`](TypeMap) to store the dependencies by their type.
-#[derive(Default)]
-pub struct DIManager(TypeMap);
 
-impl DIManager {
-    /// Builds a dependency using the dependency injection manager.
+    /// Removes a value from the map by its type, returning it if it was found.
     ///
-    /// The `build` method is used to build a dependency using the dependency injection manager. The method takes a type parameter `T`
-    /// that must implement the [`DIBuilder`](DIBuilder) trait. Afterward, it returns a [`DIObj`](DIObj) that wraps the built dependency
-    /// and stores it in the dependency injection manager.
+    /// # Examples
     ///
-    /// The method returns `None` if the dependency could not be built.
+    /// ```
+    /// use yadir::core::primitives::TypeMap;
+    ///
+    /// let mut map = TypeMap::default();
+    /// map.set(42, None);
+    ///
+    /// assert_eq!(map.remove::<i32>(), Some(42));
+    /// assert!(!map.has::<i32>());
+    /// ```
+    pub fn remove<T>(&mut self) -> Option<T>
+    where
+        T: Any + 'static,
+    {
+        self.0.remove(&TypeId::of::<T>()).and_then(Slot::into_inner)
+    }
+
+    /// Gets the entry of a given type for in-place manipulation.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use async_trait::async_trait;
-    /// # use yadir::{deps, let_deps};
-    /// # use yadir::core::contracts::{DIBuilder};
-    /// # use yadir::core::primitives::DIObj;
-    /// use yadir::core::primitives::DIManager;
-    /// # use yadir_derive::DIBuilder;
+    /// use yadir::core::primitives::TypeMap;
     ///
-    /// # #[derive(Clone, DIBuilder)]
-    /// # struct Bar;
-    /// #
-    /// # #[derive(Clone, DIBuilder)]
-    /// # struct Foo(#[deps] Bar);
-    /// #
-    /// #[tokio::main]
-    /// async fn main() {
-    ///     let mut manager = DIManager::default();
+    /// let mut map = TypeMap::default();
     ///
-    ///     manager.build::<Bar>().await;
-    ///     manager.build::<Foo>().await;
+    /// map.entry::<Vec<&str>>().or_default().push("first");
+    /// map.entry::<Vec<&str>>().or_default().push("second");
     ///
-    ///     assert!(manager.has::<DIObj<Bar>>());
-    /// }
+    /// assert_eq!(map.get::<Vec<&str>>(), Some(&vec!["first", "second"]));
     /// ```
-    pub async fn build<T>(&mut self) -> Option<DIObj<T::Output>>
+    pub fn entry<T>(&mut self) -> Entry<'_, T>
     where
-        T: DIBuilder,
+        T: Clone + Send + Sync + 'static,
     {
-        let input = T::Input::get_input(self)?;
-        let obj = T::build(input).await;
-        let sync_obj = DIObj::new(obj);
-        self.0
-            .set::<DIObj<T::Output>>(sync_obj.clone(), Some(Lifetime::Transient));
+        Entry {
+            entry: self.0.entry(TypeId::of::<T>()),
+            marker: PhantomData,
+        }
+    }
 
-        Some(sync_obj)
+    /// Returns the number of values stored in the map.
+    pub fn len(&self) -> usize {
+        self.0.len()
     }
 
-    /// Registers a dependency using the dependency injection manager with an optional lifetime and returns a mutable reference to the manager allowing for further chaining.
+    /// Checks if the map does not contain any value.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Removes all the values from the map.
+    pub fn clear(&mut self) {
+        self.0.clear()
+    }
+
+    /// Returns an iterator over the names of the types stored in the map, in arbitrary order.
     ///
     /// # Examples
     ///
     /// ```
-    /// use async_trait::async_trait;
-    /// use yadir::{deps, let_deps};
-    /// use yadir::core::contracts::{DIBuilder};
-    /// use yadir::core::primitives::{DIManager, DIObj};
-    /// use yadir_derive::DIBuilder;
-    ///
-    /// #[derive(Clone, DIBuilder)]
-    /// struct Bar;
-    ///
-    /// #[derive(Clone, DIBuilder)]
-    /// struct Foo(#[deps] Bar);
-    ///
-    /// #[tokio::main]
-    /// async fn main() {
-    ///     let mut manager = DIManager::default();
+    /// use yadir::core::primitives::TypeMap;
     ///
-    ///     manager
-    ///         .register::<Bar>(None).await
-    ///         .register::<Foo>(None).await;
+    /// let mut map = TypeMap::default();
+    /// map.set(42, None);
     ///
-    ///     assert!(manager.has::<DIObj<Bar>>());
-    ///     assert!(manager.has::<DIObj<Foo>>());
-    /// }
+    /// assert_eq!(map.type_names().collect::<Vec<_>>(), ["i32"]);
     /// ```
-    pub async fn register<T>(&mut self, lifetime: Option<Lifetime>) -> &mut Self
+    pub fn type_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.0.values().map(|slot| slot.type_name)
+    }
+}
+
+impl Debug for TypeMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.type_names()).finish()
+    }
+}
+
+/// A view into the value of a given type stored in a [`TypeMap`](TypeMap), obtained via [`TypeMap::entry`](TypeMap::entry).
+pub struct Entry<'a, T> {
+    entry: hash_map::Entry<'a, TypeId, Slot>,
+    marker: PhantomData<T>,
+}
+
+impl<'a, T> Entry<'a, T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// Inserts the given value if the entry is vacant and returns a mutable reference to the value of the entry.
+    pub fn or_insert(self, default: T) -> &'a mut T {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the value returned by the given closure if the entry is vacant and returns a mutable reference to the value of the entry.
+    pub fn or_insert_with(self, default: impl FnOnce() -> T) -> &'a mut T {
+        self.entry
+            .or_insert_with(|| Slot::new(default(), Lifetime::default()))
+            .downcast_mut()
+            .expect("values are keyed by their own type")
+    }
+
+    /// Inserts the default value if the entry is vacant and returns a mutable reference to the value of the entry.
+    pub fn or_default(self) -> &'a mut T
     where
-        T: DIBuilder,
+        T: Default,
     {
-        let input = T::Input::get_input(self)
-            .expect("Some input dependencies are missing. Please register them beforehand.");
-        let obj = T::build(input).await;
-        let sync_obj = DIObj::new(obj);
-        self.0.set::<DIObj<T::Output>>(sync_obj.clone(), lifetime);
+        self.or_insert_with(T::default)
+    }
+
+    /// Modifies the value in place if the entry is occupied.
+    pub fn and_modify(mut self, f: impl FnOnce(&mut T)) -> Self {
+        if let hash_map::Entry::Occupied(entry) = &mut self.entry {
+            if let Some(value) = entry.get_mut().downcast_mut() {
+                f(value);
+            }
+        }
 
         self
     }
+}
 
-    /// Resolves a dependency using the dependency injection manager.
+/// An error returned when accessing a [`DIObj`](DIObj) whose value was poisoned.
+///
+/// A value gets poisoned when a thread panics while holding a write guard to it, as the value might have been left in an
+/// inconsistent state. Replacing the value via [`DIObj::replace`](DIObj::replace) clears the poisoning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoisonedError;
+
+impl Display for PoisonedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the dependency was poisoned by a panic while being mutated"
+        )
+    }
+}
+
+impl Error for PoisonedError {}
+
+/// The value shared by all the clones of a [`DIObj`](DIObj), along with its poisoning flag.
+struct Shared<T> {
+    value: RwLock<T>,
+    poisoned: AtomicBool,
+}
+
+impl<T> Shared<T> {
+    fn check(&self) -> Result<(), PoisonedError> {
+        match self.poisoned.load(Ordering::Acquire) {
+            true => Err(PoisonedError),
+            false => Ok(()),
+        }
+    }
+}
+
+/// A new type wrapper for a thread-safe reference counted read-write lock to handle thread-safe sharing of embedded dependencies.
+///
+/// The embedded value can be accessed:
+/// - by cloning it via [`extract`](DIObj::extract), if the value is `Clone`;
+/// - by borrowing it via the [`read`](DIObj::read) and [`write`](DIObj::write) guards, or via the [`with`](DIObj::with)
+///   and [`with_mut`](DIObj::with_mut) closures, which block the current thread until the lock is acquired;
+/// - by borrowing it via the [`read_async`](DIObj::read_async) and [`write_async`](DIObj::write_async) guards, which
+///   wait for the lock without blocking the executor and can be held across `.await` points.
+///
+/// The embedded value does not need to be `Clone`, as cloning a [`DIObj`](DIObj) only clones the handle.
+pub struct DIObj<T>(Arc<Shared<T>>);
+
+impl<T> DIObj<T> {
+    pub fn new(t: T) -> Self {
+        Self(Arc::new(Shared {
+            value: RwLock::new(t),
+            poisoned: AtomicBool::new(false),
+        }))
+    }
+
+    /// Atomically replaces the embedded value, returning the previous one.
+    ///
+    /// As all the clones of a [`DIObj`](DIObj) share the same value, the change is visible to every holder. As the whole
+    /// value is replaced, a poisoned value is recovered.
     ///
     /// # Examples
     ///
     /// ```
-    /// use async_trait::async_trait;
-    /// use yadir::{deps, let_deps};
-    /// use yadir::core::contracts::{DIBuilder};
-    /// use yadir::core::primitives::{DIManager, DIObj};
-    /// use yadir_derive::DIBuilder;
+    /// use yadir::core::primitives::DIObj;
     ///
-    /// #[derive(Clone, DIBuilder)]
-    /// struct Bar;
+    /// let obj = DIObj::new(42);
+    /// let shared = obj.clone();
     ///
-    /// #[derive(Clone, DIBuilder)]
-    /// struct Foo(#[deps] Bar);
+    /// assert_eq!(obj.replace(43), 42);
+    /// assert_eq!(shared.extract(), 43);
+    /// ```
+    pub fn replace(&self, t: T) -> T {
+        let mut value = self.0.value.write_blocking();
+        self.0.poisoned.store(false, Ordering::Release);
+
+        std::mem::replace(&mut *value, t)
+    }
+
+    /// Locks the embedded value for reading, blocking the current thread until no writer holds the lock.
     ///
-    /// #[tokio::main]
-    /// async fn main() {
-    ///     let mut manager = DIManager::default();
+    /// Several readers can hold the lock at the same time. As the guard blocks the thread while waiting, prefer
+    /// [`read_async`](DIObj::read_async) in async code.
     ///
-    ///     manager.build::<Bar>().await;
-    ///     manager.build::<Foo>().await;    
+    /// # Examples
     ///
-    ///     let foo = manager.resolve::<Foo>().await;
+    /// ```
+    /// use yadir::core::primitives::DIObj;
     ///
-    ///     assert!(foo.is_some());
-    /// }
+    /// let obj = DIObj::new(vec![1, 2, 3]);
+    ///
+    /// let first = obj.read().unwrap();
+    /// let second = obj.read().unwrap();
+    ///
+    /// assert_eq!(first.len() + second.len(), 6);
     /// ```
-    pub async fn resolve<T>(&mut self) -> Option<DIObj<T::Output>>
-    where
-        T: DIBuilder,
-    {
-        match self.0.get_lifetime::<DIObj<T::Output>>() {
-            Some(Lifetime::Transient) => self.build::<T>().await,
-            Some(Lifetime::Singleton) => {
-                let obj = self.0.get::<DIObj<T::Output>>().unwrap().extract();
-                let sync_obj = DIObj::new(obj);
-                Some(sync_obj)
-            }
-            None => None,
-        }
+    pub fn read(&self) -> Result<DIObjReadGuard<'_, T>, PoisonedError> {
+        let guard = self.0.value.read_blocking();
+        self.0.check()?;
+
+        Ok(DIObjReadGuard(guard))
     }
 
-    /// Checks if the dependency injection manager contains a dependency of a given type.
+    /// Locks the embedded value for writing, blocking the current thread until no other guard holds the lock.
     ///
-    /// The `has` method is used to check if the dependency injection manager contains a dependency of a given type.
+    /// If the thread panics while holding the guard, the value is poisoned. As the guard blocks the thread while waiting,
+    /// prefer [`write_async`](DIObj::write_async) in async code.
     ///
     /// # Examples
     ///
     /// ```
-    /// use async_trait::async_trait;
-    /// use yadir::{deps, let_deps};
-    /// use yadir::core::contracts::{DIBuilder};
-    /// use yadir::core::primitives::{DIObj};
-    /// use yadir::core::primitives::DIManager;
-    /// use yadir_derive::DIBuilder;
+    /// use yadir::core::primitives::DIObj;
     ///
-    /// #[derive(Clone, DIBuilder)]
-    /// struct Bar;
+    /// let obj = DIObj::new(vec![1, 2, 3]);
+    /// obj.write().unwrap().push(4);
     ///
-    /// #[derive(Clone, DIBuilder)]
-    /// struct Foo(#[deps] Bar);
+    /// assert_eq!(obj.extract(), vec![1, 2, 3, 4]);
+    /// ```
+    pub fn write(&self) -> Result<DIObjWriteGuard<'_, T>, PoisonedError> {
+        let guard = self.0.value.write_blocking();
+        self.0.check()?;
+
+        Ok(DIObjWriteGuard::new(guard, &self.0.poisoned))
+    }
+
+    /// Locks the embedded value for reading, waiting asynchronously until no writer holds the lock.
+    ///
+    /// The returned guard is `Send` whenever `T` is `Sync`, so it can be held across `.await` points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::primitives::DIObj;
     ///
     /// #[tokio::main]
     /// async fn main() {
-    ///     let mut manager = DIManager::default();
+    ///     let obj = DIObj::new(String::from("config"));
     ///
-    ///     manager.build::<Bar>().await;
-    ///     manager.build::<Foo>().await;
+    ///     let value = obj.read_async().await.unwrap();
+    ///     tokio::task::yield_now().await;
     ///
-    ///     assert!(manager.has::<DIObj<Bar>>());
+    ///     assert_eq!(*value, "config");
+    /// }
+    /// ```
+    pub async fn read_async(&self) -> Result<DIObjReadGuard<'_, T>, PoisonedError> {
+        let guard = self.0.value.read().await;
+        self.0.check()?;
+
+        Ok(DIObjReadGuard(guard))
+    }
+
+    /// Locks the embedded value for writing, waiting asynchronously until no other guard holds the lock.
+    ///
+    /// The returned guard is `Send` whenever `T` is `Send`, so it can be held across `.await` points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::primitives::DIObj;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let obj = DIObj::new(0);
+    ///
+    ///     let mut value = obj.write_async().await.unwrap();
+    ///     tokio::task::yield_now().await;
+    ///     *value += 1;
+    ///     drop(value);
+    ///
+    ///     assert_eq!(obj.extract(), 1);
+    /// }
+    /// ```
+    pub async fn write_async(&self) -> Result<DIObjWriteGuard<'_, T>, PoisonedError> {
+        let guard = self.0.value.write().await;
+        self.0.check()?;
+
+        Ok(DIObjWriteGuard::new(guard, &self.0.poisoned))
+    }
+
+    /// Calls the given closure with a shared reference to the embedded value, without cloning it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::primitives::DIObj;
+    ///
+    /// let obj = DIObj::new(vec![1, 2, 3]);
+    ///
+    /// assert_eq!(obj.with(|numbers| numbers.iter().sum::<i32>()), Ok(6));
+    /// ```
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> Result<R, PoisonedError> {
+        self.read().map(|guard| f(&guard))
+    }
+
+    /// Calls the given closure with a mutable reference to the embedded value, without cloning it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::primitives::DIObj;
+    ///
+    /// let obj = DIObj::new(vec![1, 2, 3]);
+    /// let shared = obj.clone();
+    ///
+    /// assert_eq!(obj.with_mut(|numbers| numbers.pop()), Ok(Some(3)));
+    /// assert_eq!(shared.extract(), vec![1, 2]);
+    /// ```
+    pub fn with_mut<R>(&self, f: impl FnOnce(&mut T) -> R) -> Result<R, PoisonedError> {
+        self.write().map(|mut guard| f(&mut guard))
+    }
+
+    /// Returns `true` if the value was poisoned by a panic while being mutated.
+    pub fn is_poisoned(&self) -> bool {
+        self.0.poisoned.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` if both handles share the same embedded value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::primitives::DIObj;
+    ///
+    /// let obj = DIObj::new(42);
+    ///
+    /// assert!(obj.ptr_eq(&obj.clone()));
+    /// assert!(!obj.ptr_eq(&DIObj::new(42)));
+    /// ```
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Creates a [`WeakDIObj`](WeakDIObj) handle to the embedded value, which does not keep it alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::primitives::DIObj;
+    ///
+    /// let obj = DIObj::new(42);
+    /// let weak = obj.downgrade();
+    ///
+    /// assert!(weak.upgrade().is_some_and(|upgraded| upgraded.ptr_eq(&obj)));
+    ///
+    /// drop(obj);
+    /// assert!(weak.upgrade().is_none());
+    /// ```
+    pub fn downgrade(&self) -> WeakDIObj<T> {
+        WeakDIObj(Arc::downgrade(&self.0))
+    }
+}
+
+impl<T: Clone> DIObj<T> {
+    /// Returns a clone of the embedded value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is poisoned, see [`try_extract`](DIObj::try_extract) for a non-panicking alternative.
+    pub fn extract(&self) -> T {
+        self.try_extract().unwrap()
+    }
+
+    /// Returns a clone of the embedded value, or an error if the value is poisoned.
+    pub fn try_extract(&self) -> Result<T, PoisonedError> {
+        self.with(T::clone)
+    }
+}
+
+impl<T> Clone for DIObj<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T: Debug> Debug for DIObj<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut d = f.debug_struct("DIObj");
+        match self.0.value.try_read() {
+            Some(value) => d.field("data", &&*value),
+            None => d.field("data", &format_args!("<locked>")),
+        };
+        d.field("poisoned", &self.is_poisoned()).finish()
+    }
+}
+
+/// A weak handle to the value embedded in a [`DIObj`](DIObj), created via [`DIObj::downgrade`](DIObj::downgrade).
+///
+/// The handle does not keep the value alive, so it can be used to break reference cycles between dependencies or to
+/// observe a dependency without extending its lifetime.
+pub struct WeakDIObj<T>(Weak<Shared<T>>);
+
+impl<T> WeakDIObj<T> {
+    /// Returns a [`DIObj`](DIObj) handle to the embedded value, or `None` if it has already been dropped.
+    pub fn upgrade(&self) -> Option<DIObj<T>> {
+        self.0.upgrade().map(DIObj)
+    }
+}
+
+impl<T> Clone for WeakDIObj<T> {
+    fn clone(&self) -> Self {
+        Self(Weak::clone(&self.0))
+    }
+}
+
+impl<T> Debug for WeakDIObj<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(WeakDIObj)")
+    }
+}
+
+/// A guard providing shared access to the value embedded in a [`DIObj`](DIObj), released when dropped.
+pub struct DIObjReadGuard<'a, T>(RwLockReadGuard<'a, T>);
+
+impl<T> Deref for DIObjReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Debug> Debug for DIObjReadGuard<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+/// A guard providing exclusive access to the value embedded in a [`DIObj`](DIObj), released when dropped.
+///
+/// The value is poisoned if the guard is dropped while its thread is panicking.
+pub struct DIObjWriteGuard<'a, T> {
+    guard: RwLockWriteGuard<'a, T>,
+    poisoned: &'a AtomicBool,
+    panicking: bool,
+}
+
+impl<'a, T> DIObjWriteGuard<'a, T> {
+    fn new(guard: RwLockWriteGuard<'a, T>, poisoned: &'a AtomicBool) -> Self {
+        Self {
+            guard,
+            poisoned,
+            panicking: thread::panicking(),
+        }
+    }
+}
+
+impl<T> Deref for DIObjWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for DIObjWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+impl<T: Debug> Debug for DIObjWriteGuard<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> Drop for DIObjWriteGuard<'_, T> {
+    fn drop(&mut self) {
+        // only poison the value if the panic started while the guard was held
+        if !self.panicking && thread::panicking() {
+            self.poisoned.store(true, Ordering::Release);
+        }
+    }
+}
+
+/// A callback invoked with the new value whenever a singleton is swapped at runtime.
+type Subscriber<T> = Arc<dyn Fn(&DIObj<T>) + Send + Sync>;
+
+/// A function mapping a registered dependency into an additional output type, see [`forward`](DIManager::forward).
+type Forwarder<From, To> = Arc<dyn Fn(DIObj<From>) -> To + Send + Sync>;
+
+/// A type-erased function used to rebuild a registered dependency from its current inputs.
+type Rebuild = for<'a> fn(&'a mut DIManager) -> Pin<Box<dyn Future<Output = bool> + Send + 'a>>;
+
+/// A type-erased function used to resolve a registered dependency via its builder, returning `false` if it could not be resolved.
+type Resolve = for<'a> fn(&'a mut DIManager) -> Pin<Box<dyn Future<Output = bool> + Send + 'a>>;
+
+/// The bookkeeping kept for each registered dependency, in registration order.
+struct Registration {
+    builder: TypeId,
+    descriptor: RegistrationDescriptor,
+    rebuild: Rebuild,
+    resolve: Resolve,
+}
+
+/// A struct used to model a dependency injection manager.
+///
+/// The `DIManager` struct is used to manage the dependencies and build them using the [`build`](DIManager::build) method.
+/// The manager uses a [`TypeMap`](TypeMap) to store the dependencies by their type.
+#[derive(Default)]
+pub struct DIManager {
+    deps: TypeMap,
+    registrations: Vec<Registration>,
+    outputs: HashMap<TypeId, usize, BuildHasherDefault<TypeIdHasher>>,
+    subscribers: TypeMap,
+    forwarders: TypeMap,
+    interceptors: Vec<Box<dyn Interceptor>>,
+}
+
+impl DIManager {
+    /// Builds a dependency using the dependency injection manager.
+    ///
+    /// The `build` method is used to build a dependency using the dependency injection manager. The method takes a type parameter `T`
+    /// that must implement the [`DIBuilder`](DIBuilder) trait. Afterward, it returns a [`DIObj`](DIObj) that wraps the built dependency
+    /// and stores it in the dependency injection manager.
+    ///
+    /// The method returns `None` if the dependency could not be built.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use yadir::deps;
+    /// # use yadir::core::contracts::{DIBuilder};
+    /// # use yadir::core::primitives::DIObj;
+    /// use yadir::core::primitives::DIManager;
+    /// # use yadir_derive::DIBuilder;
+    ///
+    /// # #[derive(Clone, DIBuilder)]
+    /// # struct Bar;
+    /// #
+    /// # #[derive(Clone, DIBuilder)]
+    /// # struct Foo(#[deps] Bar);
+    /// #
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///
+    ///     manager.build::<Bar>().await;
+    ///     manager.build::<Foo>().await;
+    ///
+    ///     assert!(manager.has::<DIObj<Bar>>());
+    /// }
+    /// ```
+    pub async fn build<T>(&mut self) -> Option<DIObj<T::Output>>
+    where
+        T: DIBuilder + 'static,
+    {
+        self.build_transient::<T>().await.ok()
+    }
+
+    /// Registers a dependency using the dependency injection manager with an optional lifetime and returns a mutable reference to the manager allowing for further chaining.
+    ///
+    /// The method panics if some input dependencies are missing, while a build vetoed by an [`Interceptor`](Interceptor) or failed by a
+    /// fallible builder, see [`DIBuilder::try_build`](DIBuilder::try_build), leaves the dependency unregistered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::primitives::{DIManager, DIObj};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Bar;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Foo(#[deps] Bar);
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///
+    ///     manager
+    ///         .register::<Bar>(None).await
+    ///         .register::<Foo>(None).await;
+    ///
+    ///     assert!(manager.has::<DIObj<Bar>>());
+    ///     assert!(manager.has::<DIObj<Foo>>());
+    /// }
+    /// ```
+    pub async fn register<T>(&mut self, lifetime: Option<Lifetime>) -> &mut Self
+    where
+        T: DIBuilder + 'static,
+    {
+        match self.construct::<T>(lifetime.unwrap_or_default()).await {
+            Ok(obj) => {
+                self.store::<T>(DIObj::new(obj), lifetime);
+                T::register_aliases(self, lifetime).await;
+            }
+            Err(ResolveError::MissingDependencies) => {
+                panic!("Some input dependencies are missing. Please register them beforehand.")
+            }
+            Err(_) => {}
+        }
+
+        self
+    }
+
+    /// Resolves a dependency using the dependency injection manager.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::primitives::{DIManager, DIObj};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Bar;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Foo(#[deps] Bar);
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///
+    ///     manager.build::<Bar>().await;
+    ///     manager.build::<Foo>().await;    
+    ///
+    ///     let foo = manager.resolve::<Foo>().await;
+    ///
+    ///     assert!(foo.is_some());
+    /// }
+    /// ```
+    pub async fn resolve<T>(&mut self) -> Option<DIObj<T::Output>>
+    where
+        T: DIBuilder + 'static,
+    {
+        self.try_resolve::<T>().await.ok()
+    }
+
+    /// Resolves a dependency like [`resolve`](DIManager::resolve), returning the reason why it could not be resolved on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::primitives::{DIManager, ResolveError};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Bar;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///
+    ///     assert_eq!(manager.try_resolve::<Bar>().await.err(), Some(ResolveError::NotRegistered));
+    /// }
+    /// ```
+    pub async fn try_resolve<T>(&mut self) -> Result<DIObj<T::Output>, ResolveError>
+    where
+        T: DIBuilder + 'static,
+    {
+        let Some(lifetime) = self.deps.get_lifetime::<DIObj<T::Output>>() else {
+            return Err(self.report::<T>(ResolveError::NotRegistered));
+        };
+
+        let resolution = async {
+            let obj = match lifetime {
+                Lifetime::Transient => self.build_transient::<T>().await?,
+                Lifetime::Singleton => self
+                    .deps
+                    .get::<DIObj<T::Output>>()
+                    .cloned()
+                    .ok_or(ResolveError::NotRegistered)?,
+            };
+            self.report_resolved::<T>(lifetime);
+
+            Ok(obj)
+        };
+
+        telemetry::in_resolve_span(type_name::<T>(), lifetime, resolution).await
+    }
+
+    /// Registers a dependency under an additional output type, see [`DIBuilderAs`](DIBuilderAs).
+    ///
+    /// The additional output is built, stored and resolved independently of the main output of the builder, hence a singleton
+    /// registered under several output types is built once per output type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use yadir::deps;
+    /// use yadir::core::contracts::{DIBuilder, DIBuilderAs};
+    /// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+    ///
+    /// trait Printer: Send + Sync {
+    ///     fn print(&self) -> String;
+    /// }
+    ///
+    /// struct Bar;
+    ///
+    /// impl Printer for Bar {
+    ///     fn print(&self) -> String {
+    ///         "bar".to_string()
+    ///     }
+    /// }
+    ///
+    /// impl DIBuilder for Bar {
+    ///     type Input = deps!();
+    ///     type Output = Self;
+    ///
+    ///     async fn build(_: Self::Input) -> Self::Output {
+    ///         Self
+    ///     }
+    /// }
+    ///
+    /// impl DIBuilderAs<Arc<dyn Printer>> for Bar {
+    ///     async fn build_as(_: Self::Input) -> Arc<dyn Printer> {
+    ///         Arc::new(Self)
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///     manager
+    ///         .register_as::<Bar, Arc<dyn Printer>>(Some(Lifetime::Singleton))
+    ///         .await;
+    ///
+    ///     assert!(manager.has::<DIObj<Arc<dyn Printer>>>());
+    ///     assert!(!manager.has::<DIObj<Bar>>());
+    /// }
+    /// ```
+    pub async fn register_as<T, O>(&mut self, lifetime: Option<Lifetime>) -> &mut Self
+    where
+        T: DIBuilderAs<O> + 'static,
+        O: Send + Sync + 'static,
+    {
+        self.register::<Alias<T, O>>(lifetime).await
+    }
+
+    /// Registers a dependency under an additional output type, like [`register_as`](DIManager::register_as), behind a boxed future.
+    ///
+    /// Used by `#[derive(DIBuilder)]` to register the additional outputs, as proving that the future of `register_as` is `Send` for
+    /// concrete trait object outputs, e.g. `Box<dyn Printer>`, requires the compiler to prove it for every lifetime of the trait object.
+    #[doc(hidden)]
+    pub fn register_alias<T, O>(
+        &mut self,
+        lifetime: Option<Lifetime>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + '_>>
+    where
+        T: DIBuilderAs<O> + 'static,
+        O: Send + Sync + 'static,
+    {
+        Box::pin(async move {
+            self.register_as::<T, O>(lifetime).await;
+        })
+    }
+
+    /// Registers a provider with the lifetime it was declared with, see [`Provider`](Provider).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir_derive::provider;
+    /// use yadir::core::primitives::{DIManager, DIObj};
+    ///
+    /// struct Options {
+    ///     root: String,
+    /// }
+    ///
+    /// #[provider]
+    /// fn make_options() -> Options {
+    ///     Options { root: "/tmp".to_string() }
+    /// }
+    ///
+    /// #[provider]
+    /// fn make_path(options: DIObj<Options>) -> std::path::PathBuf {
+    ///     std::path::PathBuf::from(&options.read().unwrap().root)
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///     manager
+    ///         .register_provider(make_options)
+    ///         .await
+    ///         .register_provider(make_path)
+    ///         .await;
+    ///
+    ///     assert!(manager.has::<DIObj<std::path::PathBuf>>());
+    /// }
+    /// ```
+    pub async fn register_provider<P>(&mut self, _provider: P) -> &mut Self
+    where
+        P: Provider + 'static,
+    {
+        self.register::<P>(P::LIFETIME).await
+    }
+
+    /// Resolves a dependency registered under an additional output type, see [`register_as`](DIManager::register_as).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// trait Printer: Send + Sync {
+    ///     fn print(&self) -> String;
+    /// }
+    ///
+    /// #[derive(DIBuilder)]
+    /// #[build_as(Box<dyn Printer>, Arc<dyn Printer>)]
+    /// struct Bar;
+    ///
+    /// impl Printer for Bar {
+    ///     fn print(&self) -> String {
+    ///         "bar".to_string()
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///     manager.register::<Bar>(Some(Lifetime::Transient)).await;
+    ///
+    ///     let printer = manager.resolve_as::<Bar, Arc<dyn Printer>>().await;
+    ///
+    ///     assert_eq!(printer.unwrap().read().unwrap().print(), "bar");
+    /// }
+    /// ```
+    pub async fn resolve_as<T, O>(&mut self) -> Option<DIObj<O>>
+    where
+        T: DIBuilderAs<O> + 'static,
+        O: Send + Sync + 'static,
+    {
+        self.resolve::<Alias<T, O>>().await
+    }
+
+    /// Resolves a dependency by its output type, without naming the builder it was registered with.
+    ///
+    /// The dependency is resolved via the builder registered under the output type, honouring the lifetime it was registered with,
+    /// i.e. a transient is built again on each call while a singleton is shared. When several builders have been registered under
+    /// the output type, e.g. via [`register_as`](DIManager::register_as) or [`forward`](DIManager::forward), the last one wins.
+    /// The method returns `None` if no builder has been registered under the output type, in which case the installed interceptors
+    /// are notified with the output type standing for the missing builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// trait Printer: Send + Sync {
+    ///     fn print(&self) -> String;
+    /// }
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// #[build_as(Box<dyn Printer>)]
+    /// struct Bar;
+    ///
+    /// impl Printer for Bar {
+    ///     fn print(&self) -> String {
+    ///         "bar".to_string()
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///     manager.register::<Bar>(Some(Lifetime::Transient)).await;
+    ///
+    ///     let printer = manager.get::<Box<dyn Printer>>().await;
+    ///
+    ///     assert_eq!(printer.unwrap().read().unwrap().print(), "bar");
+    /// }
+    /// ```
+    pub async fn get<O>(&mut self) -> Option<DIObj<O>>
+    where
+        O: Send + Sync + 'static,
+    {
+        let output = TypeId::of::<O>();
+        let Some(registration) = self.registration(output) else {
+            self.report_unregistered::<O>();
+            return None;
+        };
+
+        match (registration.resolve)(self).await {
+            true => self.deps.get::<DIObj<O>>().cloned(),
+            false => None,
+        }
+    }
+
+    /// Resolves a dependency by its output type, see [`get`](DIManager::get).
+    pub async fn get_service<O>(&mut self) -> Option<DIObj<O>>
+    where
+        O: Send + Sync + 'static,
+    {
+        self.get::<O>().await
+    }
+
+    /// Forwards a registered dependency to an additional output type and returns a mutable reference to the manager allowing for further chaining.
+    ///
+    /// The `map` function receives the shared [`DIObj`](DIObj) handle of the dependency registered under the `From` output type, so that
+    /// the `To` output, e.g. a trait object wrapping the handle, points at the same underlying instance. The forwarded output is registered
+    /// with the lifetime of the `From` dependency, i.e. it is mapped once from a singleton, while it is mapped from a newly built instance
+    /// on each call to [`get`](DIManager::get) for a transient. It can also be injected as an input dependency like any other registration.
+    ///
+    /// If no dependency has been registered under the `From` output type, the `To` output is left unregistered and the failure is
+    /// reported to the installed interceptors like in [`get`](DIManager::get).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicU8, Ordering};
+    /// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// trait Reader: Send + Sync {
+    ///     fn read(&self) -> u8;
+    /// }
+    ///
+    /// trait Writer: Send + Sync {
+    ///     fn write(&self, value: u8);
+    /// }
+    ///
+    /// #[derive(Default, DIBuilder)]
+    /// #[build_method("default")]
+    /// struct Cache(AtomicU8);
+    ///
+    /// impl Reader for DIObj<Cache> {
+    ///     fn read(&self) -> u8 {
+    ///         self.read().unwrap().0.load(Ordering::SeqCst)
+    ///     }
+    /// }
+    ///
+    /// impl Writer for DIObj<Cache> {
+    ///     fn write(&self, value: u8) {
+    ///         self.read().unwrap().0.store(value, Ordering::SeqCst)
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///     manager
+    ///         .register::<Cache>(Some(Lifetime::Singleton))
+    ///         .await
+    ///         .forward::<Cache, Box<dyn Reader>>(|cache| Box::new(cache))
+    ///         .forward::<Cache, Box<dyn Writer>>(|cache| Box::new(cache));
+    ///
+    ///     let reader = manager.get::<Box<dyn Reader>>().await.unwrap();
+    ///     let writer = manager.get::<Box<dyn Writer>>().await.unwrap();
+    ///     writer.read().unwrap().write(7);
+    ///
+    ///     assert_eq!(reader.read().unwrap().read(), 7);
+    /// }
+    /// ```
+    pub fn forward<From, To>(
+        &mut self,
+        map: impl Fn(DIObj<From>) -> To + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        From: Send + Sync + 'static,
+        To: Send + Sync + 'static,
+    {
+        let from = TypeId::of::<From>();
+        let (Some(registration), Some(obj)) = (
+            self.registration(from),
+            self.deps.get::<DIObj<From>>().cloned(),
+        ) else {
+            self.report_unregistered::<From>();
+            return self;
+        };
+
+        // the forwarded output is described as the same builder, registered under another output type
+        let descriptor = RegistrationDescriptor {
+            type_name: registration.descriptor.type_name,
+            output: TypeDescriptor::of::<To>(),
+            key: TypeDescriptor::of::<DIObj<To>>(),
+            lifetime: registration.descriptor.lifetime,
+            dependencies: vec![registration.descriptor.output],
+        };
+        let registration = Registration {
+            builder: TypeId::of::<Forwarder<From, To>>(),
+            descriptor,
+            rebuild: |manager| Box::pin(manager.reforward::<From, To>()),
+            resolve: |manager| Box::pin(manager.resolve_forwarded::<From, To>()),
+        };
+
+        self.deps
+            .set::<DIObj<To>>(DIObj::new(map(obj)), Some(registration.descriptor.lifetime));
+        self.forwarders
+            .set::<Forwarder<From, To>>(Arc::new(map), None);
+
+        self.insert_registration(registration);
+
+        self
+    }
+
+    /// Installs an [`Interceptor`](Interceptor) and returns a mutable reference to the manager allowing for further chaining.
+    ///
+    /// Interceptors are invoked in installation order, see [`Interceptor`](Interceptor) for the exact ordering of each callback.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::contracts::{DIBuilder, Interceptor};
+    /// use yadir::core::introspection::BuilderMetadata;
+    /// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Bar;
+    ///
+    /// struct Veto;
+    ///
+    /// impl Interceptor for Veto {
+    ///     fn before_build(&self, metadata: &BuilderMetadata, _: Lifetime) -> bool {
+    ///         metadata.type_name != "Bar"
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///     manager.add_interceptor(Veto);
+    ///
+    ///     assert!(manager.build::<Bar>().await.is_none());
+    /// }
+    /// ```
+    pub fn add_interceptor(&mut self, interceptor: impl Interceptor + 'static) -> &mut Self {
+        self.interceptors.push(Box::new(interceptor));
+        self
+    }
+
+    /// Builds a dependency synchronously, without blocking on any async runtime.
+    ///
+    /// The blocking counterpart of [`build`](DIManager::build) for dependencies implementing [`SyncDIBuilder`](SyncDIBuilder).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::contracts::SyncDIBuilder;
+    /// use yadir::core::primitives::{DIManager, DIObj};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// #[di(sync)]
+    /// struct Bar;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// #[di(sync)]
+    /// struct Foo(#[deps] Bar);
+    ///
+    /// let mut manager = DIManager::default();
+    ///
+    /// manager.build_sync::<Bar>();
+    /// manager.build_sync::<Foo>();
+    ///
+    /// assert!(manager.has::<DIObj<Foo>>());
+    /// ```
+    pub fn build_sync<T>(&mut self) -> Option<DIObj<T::Output>>
+    where
+        T: SyncDIBuilder + 'static,
+    {
+        self.build_transient_sync::<T>().ok()
+    }
+
+    /// Registers a dependency synchronously with an optional lifetime and returns a mutable reference to the manager allowing for further chaining.
+    ///
+    /// The blocking counterpart of [`register`](DIManager::register) for dependencies implementing [`SyncDIBuilder`](SyncDIBuilder),
+    /// which can depend on dependencies registered either synchronously or asynchronously.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::contracts::{DIBuilder, SyncDIBuilder};
+    /// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Bar;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// #[di(sync)]
+    /// struct Foo(#[deps] Bar);
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///
+    ///     manager
+    ///         .register::<Bar>(Some(Lifetime::Singleton)).await
+    ///         .register_sync::<Foo>(None);
+    ///
+    ///     assert!(manager.has::<DIObj<Foo>>());
+    /// }
+    /// ```
+    pub fn register_sync<T>(&mut self, lifetime: Option<Lifetime>) -> &mut Self
+    where
+        T: SyncDIBuilder + 'static,
+    {
+        match self.construct_sync::<T>(lifetime.unwrap_or_default()) {
+            Ok(obj) => self.store::<T>(DIObj::new(obj), lifetime),
+            Err(ResolveError::MissingDependencies) => {
+                panic!("Some input dependencies are missing. Please register them beforehand.")
+            }
+            Err(_) => {}
+        }
+
+        self
+    }
+
+    /// Resolves a dependency synchronously, building it on the spot if it has been registered as transient.
+    ///
+    /// The blocking counterpart of [`resolve`](DIManager::resolve) for dependencies implementing [`SyncDIBuilder`](SyncDIBuilder).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::contracts::SyncDIBuilder;
+    /// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// #[di(sync)]
+    /// struct Bar;
+    ///
+    /// let mut manager = DIManager::default();
+    /// manager.register_sync::<Bar>(Some(Lifetime::Transient));
+    ///
+    /// assert!(manager.resolve_sync::<Bar>().is_some());
+    /// ```
+    pub fn resolve_sync<T>(&mut self) -> Option<DIObj<T::Output>>
+    where
+        T: SyncDIBuilder + 'static,
+    {
+        self.try_resolve_sync::<T>().ok()
+    }
+
+    /// Resolves a dependency synchronously like [`resolve_sync`](DIManager::resolve_sync), returning the reason why it could not be
+    /// resolved on failure, see [`try_resolve`](DIManager::try_resolve).
+    pub fn try_resolve_sync<T>(&mut self) -> Result<DIObj<T::Output>, ResolveError>
+    where
+        T: SyncDIBuilder + 'static,
+    {
+        let Some(lifetime) = self.deps.get_lifetime::<DIObj<T::Output>>() else {
+            return Err(self.report::<T>(ResolveError::NotRegistered));
+        };
+
+        telemetry::in_resolve_span_sync(type_name::<T>(), lifetime, || {
+            let obj = match lifetime {
+                Lifetime::Transient => self.build_transient_sync::<T>()?,
+                Lifetime::Singleton => self
+                    .deps
+                    .get::<DIObj<T::Output>>()
+                    .cloned()
+                    .ok_or(ResolveError::NotRegistered)?,
+            };
+            self.report_resolved::<T>(lifetime);
+
+            Ok(obj)
+        })
+    }
+
+    /// Replaces a registered singleton at runtime and notifies its subscribers, returning the previous value.
+    ///
+    /// The value is swapped in place, so every [`DIObj`](DIObj) handle previously resolved or injected for the singleton
+    /// observes the new value. Dependencies built from the old value are left untouched, see
+    /// [`replace_singleton_with_dependents`](DIManager::replace_singleton_with_dependents) to rebuild them as well.
+    ///
+    /// The method returns `None` and leaves the manager untouched if the dependency is not registered as a singleton.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// #[build_method("default")]
+    /// struct Credentials(String);
+    ///
+    /// impl Default for Credentials {
+    ///     fn default() -> Self {
+    ///         Self("old".to_string())
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///     manager.register::<Credentials>(Some(Lifetime::Singleton)).await;
+    ///
+    ///     let credentials = manager.resolve::<Credentials>().await.unwrap();
+    ///     let previous = manager.replace_singleton::<Credentials>(Credentials("new".to_string()));
+    ///
+    ///     assert_eq!(previous.unwrap().0, "old");
+    ///     assert_eq!(credentials.extract().0, "new");
+    /// }
+    /// ```
+    pub fn replace_singleton<T>(&self, value: T::Output) -> Option<T::Output>
+    where
+        T: DIBuilder,
+    {
+        match self.deps.get_lifetime::<DIObj<T::Output>>()? {
+            Lifetime::Singleton => self.swap(value),
+            Lifetime::Transient => None,
+        }
+    }
+
+    /// Replaces a registered singleton at runtime, then rebuilds every dependency registered on top of it.
+    ///
+    /// Works like [`replace_singleton`](DIManager::replace_singleton), after which all the dependencies whose inputs
+    /// (directly or transitively) include the replaced singleton are rebuilt in registration order. Rebuilt singletons are
+    /// swapped in place and notify their own subscribers, while rebuilt transients are stored as fresh instances.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, Default, DIBuilder)]
+    /// #[build_method("default")]
+    /// struct Backend(u8);
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Service(#[deps] Backend);
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///     manager
+    ///         .register::<Backend>(Some(Lifetime::Singleton)).await
+    ///         .register::<Service>(Some(Lifetime::Singleton)).await;
+    ///
+    ///     let service = manager.resolve::<Service>().await.unwrap();
+    ///     manager.replace_singleton_with_dependents::<Backend>(Backend(1)).await;
+    ///
+    ///     assert_eq!(service.extract().0 .0, 1);
+    /// }
+    /// ```
+    pub async fn replace_singleton_with_dependents<T>(
+        &mut self,
+        value: T::Output,
+    ) -> Option<T::Output>
+    where
+        T: DIBuilder,
+    {
+        let previous = self.replace_singleton::<T>(value)?;
+
+        let mut changed = HashSet::from([TypeId::of::<T::Output>()]);
+        for index in 0..self.registrations.len() {
+            let registration = &self.registrations[index];
+            let (id, rebuild) = (registration.descriptor.output.id, registration.rebuild);
+
+            if registration
+                .descriptor
+                .dependencies
+                .iter()
+                .any(|dependency| changed.contains(&dependency.id))
+                && rebuild(self).await
+            {
+                changed.insert(id);
+            }
+        }
+
+        Some(previous)
+    }
+
+    /// Subscribes to the runtime replacements of a singleton.
+    ///
+    /// The callback is invoked with the shared [`DIObj`](DIObj) handle every time the singleton is replaced via
+    /// [`replace_singleton`](DIManager::replace_singleton) or rebuilt via
+    /// [`replace_singleton_with_dependents`](DIManager::replace_singleton_with_dependents).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::sync::atomic::{AtomicU8, Ordering};
+    /// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, Default, DIBuilder)]
+    /// #[build_method("default")]
+    /// struct Backend(u8);
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///     manager.register::<Backend>(Some(Lifetime::Singleton)).await;
+    ///
+    ///     let seen = Arc::new(AtomicU8::new(0));
+    ///     let observer = seen.clone();
+    ///     manager.subscribe::<Backend>(move |backend| observer.store(backend.extract().0, Ordering::SeqCst));
+    ///
+    ///     manager.replace_singleton::<Backend>(Backend(7));
+    ///
+    ///     assert_eq!(seen.load(Ordering::SeqCst), 7);
+    /// }
+    /// ```
+    pub fn subscribe<T>(&mut self, subscriber: impl Fn(&DIObj<T::Output>) + Send + Sync + 'static)
+    where
+        T: DIBuilder,
+    {
+        self.subscribers
+            .entry::<Vec<Subscriber<T::Output>>>()
+            .or_default()
+            .push(Arc::new(subscriber));
+    }
+
+    /// Checks if the dependency injection manager contains a dependency of a given type.
+    ///
+    /// The `has` method is used to check if the dependency injection manager contains a dependency of a given type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::primitives::{DIObj};
+    /// use yadir::core::primitives::DIManager;
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Bar;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Foo(#[deps] Bar);
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///
+    ///     manager.build::<Bar>().await;
+    ///     manager.build::<Foo>().await;
+    ///
+    ///     assert!(manager.has::<DIObj<Bar>>());
     /// }
     /// ```
     pub fn has<T>(&self) -> bool
     where
         T: Any + 'static,
     {
-        self.0.has::<T>()
+        self.deps.has::<T>()
+    }
+
+    /// Returns the descriptors of all the registered dependencies, in registration order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::any::TypeId;
+    /// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Bar;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Foo(#[deps] Bar);
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///     manager
+    ///         .register::<Bar>(Some(Lifetime::Singleton)).await
+    ///         .register::<Foo>(None).await;
+    ///
+    ///     let registrations = manager.registrations();
+    ///
+    ///     assert_eq!(registrations.len(), 2);
+    ///     assert_eq!(registrations[1].type_name, "Foo");
+    ///     assert_eq!(registrations[1].lifetime, Lifetime::Transient);
+    ///     assert_eq!(registrations[1].dependencies[0].name, "Bar");
+    ///     assert_eq!(registrations[1].key.id, TypeId::of::<DIObj<Foo>>());
+    /// }
+    /// ```
+    pub fn registrations(&self) -> Vec<RegistrationDescriptor> {
+        self.registrations
+            .iter()
+            .map(|registration| registration.descriptor.clone())
+            .collect()
+    }
+
+    /// Checks whether a dependency with the given output type has been registered.
+    #[cfg(feature = "registry")]
+    pub(crate) fn provides(&self, output: TypeId) -> bool {
+        self.outputs.contains_key(&output)
+    }
+
+    /// Returns the dependency graph of all the registered dependencies, ready to be exported as DOT, Mermaid or JSON.
+    ///
+    /// See [`DependencyGraph`](DependencyGraph) for the supported formats.
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        DependencyGraph::new(self.registrations())
+    }
+
+    /// Stores a built dependency along with the bookkeeping needed to describe and rebuild it later on.
+    fn store<T>(&mut self, obj: DIObj<T::Output>, lifetime: Option<Lifetime>)
+    where
+        T: DIBuilder + 'static,
+    {
+        self.deps.set::<DIObj<T::Output>>(obj, lifetime);
+
+        let (builder, lifetime) = (TypeId::of::<T>(), lifetime.unwrap_or_default());
+
+        // transients are stored again on each build, so avoid describing the same builder over and over
+        if let Some(&index) = self.outputs.get(&TypeId::of::<T::Output>()) {
+            let existing = &mut self.registrations[index];
+            if existing.builder == builder {
+                existing.descriptor.lifetime = lifetime;
+                return;
+            }
+        }
+
+        let registration = Registration {
+            builder,
+            descriptor: RegistrationDescriptor::new(
+                T::metadata(),
+                TypeDescriptor::of::<DIObj<T::Output>>(),
+                lifetime,
+            ),
+            rebuild: |manager| Box::pin(manager.rebuild::<T>()),
+            resolve: |manager| Box::pin(async { manager.resolve::<T>().await.is_some() }),
+        };
+
+        self.insert_registration(registration);
+    }
+
+    /// Returns the registration of the given output type, if any.
+    fn registration(&self, output: TypeId) -> Option<&Registration> {
+        self.outputs
+            .get(&output)
+            .map(|&index| &self.registrations[index])
+    }
+
+    /// Adds a registration, replacing in place the one previously registered for the same output type, if any.
+    fn insert_registration(&mut self, registration: Registration) {
+        match self.outputs.entry(registration.descriptor.output.id) {
+            hash_map::Entry::Occupied(entry) => self.registrations[*entry.get()] = registration,
+            hash_map::Entry::Vacant(entry) => {
+                entry.insert(self.registrations.len());
+                self.registrations.push(registration);
+            }
+        }
+    }
+
+    /// Rebuilds a registered dependency from its current inputs, returning `false` if some of them are missing.
+    async fn rebuild<T>(&mut self) -> bool
+    where
+        T: DIBuilder + 'static,
+    {
+        match self.deps.get_lifetime::<DIObj<T::Output>>() {
+            Some(Lifetime::Singleton) => match self.construct::<T>(Lifetime::Singleton).await {
+                Ok(obj) => self.swap(obj).is_some(),
+                Err(_) => false,
+            },
+            Some(Lifetime::Transient) => self.build::<T>().await.is_some(),
+            None => false,
+        }
+    }
+
+    /// Maps the current `From` dependency into its forwarded output, returning `false` if either of them is not registered.
+    ///
+    /// A singleton output is swapped in place, while a transient one is stored as a fresh instance mapped from a newly built `From`.
+    async fn reforward<From, To>(&mut self) -> bool
+    where
+        From: Send + Sync + 'static,
+        To: Send + Sync + 'static,
+    {
+        let Some(map) = self.forwarders.get::<Forwarder<From, To>>().cloned() else {
+            return false;
+        };
+        let Some(obj) = self.get::<From>().await else {
+            return false;
+        };
+
+        match self.deps.get_lifetime::<DIObj<To>>() {
+            Some(Lifetime::Singleton) => self.swap(map(obj)).is_some(),
+            Some(Lifetime::Transient) => {
+                self.deps
+                    .set::<DIObj<To>>(DIObj::new(map(obj)), Some(Lifetime::Transient));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Resolves a forwarded output, mapping it again from a newly built `From` for a transient.
+    async fn resolve_forwarded<From, To>(&mut self) -> bool
+    where
+        From: Send + Sync + 'static,
+        To: Send + Sync + 'static,
+    {
+        match self.deps.get_lifetime::<DIObj<To>>() {
+            Some(Lifetime::Singleton) => true,
+            Some(Lifetime::Transient) => self.reforward::<From, To>().await,
+            None => false,
+        }
+    }
+
+    /// Builds a transient dependency and stores it, returning the reason why it could not be built on failure.
+    async fn build_transient<T>(&mut self) -> Result<DIObj<T::Output>, ResolveError>
+    where
+        T: DIBuilder + 'static,
+    {
+        let obj = DIObj::new(self.construct::<T>(Lifetime::Transient).await?);
+        self.store::<T>(obj.clone(), Some(Lifetime::Transient));
+
+        Ok(obj)
+    }
+
+    /// Builds a transient dependency synchronously and stores it, see [`build_transient`](DIManager::build_transient).
+    fn build_transient_sync<T>(&mut self) -> Result<DIObj<T::Output>, ResolveError>
+    where
+        T: SyncDIBuilder + 'static,
+    {
+        let obj = DIObj::new(self.construct_sync::<T>(Lifetime::Transient)?);
+        self.store::<T>(obj.clone(), Some(Lifetime::Transient));
+
+        Ok(obj)
+    }
+
+    /// Builds a dependency from its current inputs, invoking the installed interceptors around the build.
+    async fn construct<T>(&self, lifetime: Lifetime) -> Result<T::Output, ResolveError>
+    where
+        T: DIBuilder + 'static,
+    {
+        let construction = async {
+            let (input, metadata) = self.prepare::<T>(lifetime)?;
+
+            let start = Instant::now();
+            let obj = T::try_build(input)
+                .await
+                .map_err(|error| self.report::<T>(error))?;
+            self.finish::<T>(metadata, lifetime, start.elapsed());
+
+            Ok(obj)
+        };
+
+        telemetry::in_build_span(type_name::<T>(), lifetime, construction).await
+    }
+
+    /// Builds a dependency synchronously from its current inputs, invoking the installed interceptors around the build.
+    fn construct_sync<T>(&self, lifetime: Lifetime) -> Result<T::Output, ResolveError>
+    where
+        T: SyncDIBuilder + 'static,
+    {
+        telemetry::in_build_span_sync(type_name::<T>(), lifetime, || {
+            let (input, metadata) = self.prepare::<T>(lifetime)?;
+
+            let start = Instant::now();
+            let obj =
+                <T as SyncDIBuilder>::try_build(input).map_err(|error| self.report::<T>(error))?;
+            self.finish::<T>(metadata, lifetime, start.elapsed());
+
+            Ok(obj)
+        })
+    }
+
+    /// Retrieves the inputs of a dependency about to be built, then asks the installed interceptors whether to go on.
+    ///
+    /// The builder metadata is only computed if some interceptors are installed, and is returned along with the inputs.
+    fn prepare<T>(
+        &self,
+        lifetime: Lifetime,
+    ) -> Result<(T::Input, Option<BuilderMetadata>), ResolveError>
+    where
+        T: DIBuilder + 'static,
+    {
+        let input = match T::Input::get_input(self) {
+            Some(input) => input,
+            None => return Err(self.report::<T>(ResolveError::MissingDependencies)),
+        };
+
+        let metadata = (!self.interceptors.is_empty()).then(T::metadata);
+        if let Some(metadata) = &metadata {
+            if !self
+                .interceptors
+                .iter()
+                .all(|interceptor| interceptor.before_build(metadata, lifetime))
+            {
+                return Err(self.report::<T>(ResolveError::Vetoed));
+            }
+        }
+
+        Ok((input, metadata))
+    }
+
+    /// Reports a built dependency to the telemetry and the installed interceptors.
+    fn finish<T>(&self, metadata: Option<BuilderMetadata>, lifetime: Lifetime, elapsed: Duration)
+    where
+        T: DIBuilder + 'static,
+    {
+        telemetry::record_build(type_name::<T>(), lifetime, elapsed);
+        if let Some(metadata) = &metadata {
+            self.interceptors
+                .iter()
+                .rev()
+                .for_each(|interceptor| interceptor.after_build(metadata, lifetime, elapsed));
+        }
+    }
+
+    /// Reports a build or resolution failure to the telemetry and the installed interceptors, returning the error back.
+    fn report<T>(&self, error: ResolveError) -> ResolveError
+    where
+        T: DIBuilder + 'static,
+    {
+        self.report_with(type_name::<T>(), T::metadata, error)
+    }
+
+    /// Reports a failure like [`report`](DIManager::report), the builder metadata being only computed if some interceptors are installed.
+    fn report_with(
+        &self,
+        type_name: &'static str,
+        metadata: impl FnOnce() -> BuilderMetadata,
+        error: ResolveError,
+    ) -> ResolveError {
+        telemetry::record_failure(type_name, error);
+        if !self.interceptors.is_empty() {
+            let metadata = metadata();
+            self.interceptors
+                .iter()
+                .for_each(|interceptor| interceptor.on_resolve_error(&metadata, &error));
+        }
+
+        error
+    }
+
+    /// Reports an output type under which no builder has been registered, the output type standing for the missing builder.
+    fn report_unregistered<O>(&self) -> ResolveError
+    where
+        O: 'static,
+    {
+        let metadata = || BuilderMetadata {
+            type_name: type_name::<O>(),
+            output: TypeDescriptor::of::<O>(),
+            dependencies: vec![],
+        };
+
+        self.report_with(type_name::<O>(), metadata, ResolveError::NotRegistered)
+    }
+
+    /// Reports a successful resolution to the telemetry and the installed interceptors.
+    fn report_resolved<T>(&self, lifetime: Lifetime)
+    where
+        T: DIBuilder + 'static,
+    {
+        telemetry::record_resolve(type_name::<T>(), lifetime);
+        if !self.interceptors.is_empty() {
+            let metadata = T::metadata();
+            self.interceptors
+                .iter()
+                .for_each(|interceptor| interceptor.on_resolve(&metadata, lifetime));
+        }
+    }
+
+    /// Swaps a stored dependency in place and notifies its subscribers, returning the previous value.
+    fn swap<T>(&self, value: T) -> Option<T>
+    where
+        T: 'static,
+    {
+        let obj = self.deps.get::<DIObj<T>>()?;
+        let previous = obj.replace(value);
+
+        if let Some(subscribers) = self.subscribers.get::<Vec<Subscriber<T>>>() {
+            subscribers.iter().for_each(|subscriber| subscriber(obj));
+        }
+
+        Some(previous)
+    }
+}
+
+impl Debug for DIManager {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DIManager")
+            .field("registrations", &self.registrations())
+            .finish_non_exhaustive()
     }
 }
 
-impl<T: Clone + 'static> GetInput for DIObj<T> {
+impl<T: 'static> GetInput for DIObj<T> {
     fn get_input(manager: &DIManager) -> Option<Self> {
-        manager.0.get::<Self>().cloned()
+        telemetry::in_inject_span(type_name::<T>(), || manager.deps.get::<Self>().cloned())
+    }
+
+    fn dependencies() -> Vec<TypeDescriptor> {
+        vec![TypeDescriptor::of::<T>()]
     }
 }
 
@@ -335,14 +2017,50 @@ impl GetInput for () {
     fn get_input(_: &DIManager) -> Option<Self> {
         Some(())
     }
-}
 
-impl<S, T> GetInput for (S, T)
-where
-    S: GetInput,
-    T: GetInput,
-{
-    fn get_input(manager: &DIManager) -> Option<Self> {
-        S::get_input(manager).and_then(|s| T::get_input(manager).map(|t| (s, t)))
+    fn dependencies() -> Vec<TypeDescriptor> {
+        vec![]
     }
 }
+
+/// Implements [`GetInput`](GetInput) for tuples by resolving their elements in order, so that the dependencies can be given either
+/// as a flat tuple, e.g. `(DIObj<A>, DIObj<B>, DIObj<C>)`, or as nested pairs, e.g. `(DIObj<A>, (DIObj<B>, (DIObj<C>, ())))`.
+macro_rules! impl_get_input_for_tuples {
+    ($( ($( $t:ident ),+) ),+ $(,)?) => {
+        $(
+            impl<$( $t ),+> GetInput for ($( $t, )+)
+            where
+                $( $t: GetInput, )+
+            {
+                fn get_input(manager: &DIManager) -> Option<Self> {
+                    Some(($( $t::get_input(manager)?, )+))
+                }
+
+                fn dependencies() -> Vec<TypeDescriptor> {
+                    let mut dependencies = vec![];
+                    $( dependencies.extend($t::dependencies()); )+
+                    dependencies
+                }
+            }
+        )+
+    };
+}
+
+impl_get_input_for_tuples!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H),
+    (A, B, C, D, E, F, G, H, I),
+    (A, B, C, D, E, F, G, H, I, J),
+    (A, B, C, D, E, F, G, H, I, J, K),
+    (A, B, C, D, E, F, G, H, I, J, K, L),
+    (A, B, C, D, E, F, G, H, I, J, K, L, M),
+    (A, B, C, D, E, F, G, H, I, J, K, L, M, N),
+    (A, B, C, D, E, F, G, H, I, J, K, L, M, N, O),
+    (A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P),
+);
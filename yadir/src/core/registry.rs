@@ -0,0 +1,129 @@
+//! Link-time registry of the components to be registered automatically, enabled via the `registry` feature.
+//!
+//! Deriving [`DIBuilder`](crate::core::contracts::DIBuilder) with `#[di(auto_register)]` submits the struct as a [`Component`](Component)
+//! to a distributed registry collected across all the linked crates, so that [`DIManager::from_registered_components`](DIManager::from_registered_components)
+//! can discover and register every annotated type without having to list them by hand.
+
+use crate::core::contracts::DIBuilder;
+use crate::core::introspection::BuilderMetadata;
+use crate::core::primitives::{DIManager, Lifetime};
+use std::future::Future;
+use std::pin::Pin;
+
+#[doc(hidden)]
+pub use inventory;
+
+type Register = for<'a> fn(
+    &'a mut DIManager,
+    Option<Lifetime>,
+) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+/// A struct used to model a component submitted to the registry, along with the lifetime it is registered with.
+///
+/// Components are usually submitted via `#[di(auto_register)]`, but they can also be submitted by hand via `inventory::submit!`.
+pub struct Component {
+    lifetime: Option<Lifetime>,
+    metadata: fn() -> BuilderMetadata,
+    register: Register,
+}
+
+impl Component {
+    /// Creates a component which registers the given builder with the given lifetime.
+    pub const fn new<T>(lifetime: Option<Lifetime>) -> Self
+    where
+        T: DIBuilder + 'static,
+    {
+        Self {
+            lifetime,
+            metadata: T::metadata,
+            register: register_component::<T>,
+        }
+    }
+
+    /// Returns the metadata of the builder of the component.
+    pub fn metadata(&self) -> BuilderMetadata {
+        (self.metadata)()
+    }
+
+    /// Returns the lifetime the component is registered with.
+    pub fn lifetime(&self) -> Option<Lifetime> {
+        self.lifetime
+    }
+}
+
+fn register_component<T>(
+    manager: &mut DIManager,
+    lifetime: Option<Lifetime>,
+) -> Pin<Box<dyn Future<Output = ()> + Send + '_>>
+where
+    T: DIBuilder + 'static,
+{
+    Box::pin(async move {
+        manager.register::<T>(lifetime).await;
+    })
+}
+
+inventory::collect!(Component);
+
+impl DIManager {
+    /// Creates a dependency injection manager with all the components submitted to the registry across the linked crates.
+    ///
+    /// The components are registered once all of their input dependencies have been registered, regardless of the order
+    /// in which they were linked.
+    ///
+    /// # Panics
+    ///
+    /// The method panics if the input dependencies of some components are neither registered nor submitted to the registry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yadir::core::primitives::{DIManager, DIObj};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// #[di(auto_register)]
+    /// struct Foo(#[deps] Bar);
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// #[di(lifetime = "singleton", auto_register)]
+    /// struct Bar;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::from_registered_components().await;
+    ///
+    ///     assert!(manager.resolve::<Foo>().await.is_some());
+    /// }
+    /// ```
+    pub async fn from_registered_components() -> Self {
+        let mut manager = Self::default();
+        let mut pending = inventory::iter::<Component>
+            .into_iter()
+            .map(|component| (component, component.metadata()))
+            .collect::<Vec<_>>();
+
+        while !pending.is_empty() {
+            let ready = pending.iter().position(|(_, metadata)| {
+                metadata
+                    .dependencies
+                    .iter()
+                    .all(|dependency| manager.provides(dependency.id))
+            });
+
+            let Some(index) = ready else {
+                let names = pending
+                    .iter()
+                    .map(|(_, metadata)| metadata.type_name)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                panic!("Some input dependencies of the registered components are missing: {names}.")
+            };
+
+            let (component, _) = pending.remove(index);
+            (component.register)(&mut manager, component.lifetime).await;
+        }
+
+        manager
+    }
+}
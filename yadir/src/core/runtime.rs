@@ -0,0 +1,82 @@
+//! Tokio-specific extensions of the dependency injection manager, enabled via the `tokio` feature.
+//!
+//! The core of `yadir` is executor-independent, so that [`DIManager`](DIManager) can be driven by any async runtime
+//! (or even a minimal executor such as `futures::executor::block_on`). The helpers below rely on the tokio timer and
+//! hence must be awaited from within a tokio runtime with the time driver enabled.
+
+use crate::core::contracts::DIBuilder;
+use crate::core::primitives::{DIManager, DIObj, Lifetime};
+use std::time::Duration;
+use tokio::time::error::Elapsed;
+use tokio::time::timeout;
+
+impl DIManager {
+    /// Registers a dependency like [`register`](DIManager::register), giving up if building it takes longer than the given duration.
+    ///
+    /// The method returns an [`Elapsed`](Elapsed) error and leaves the dependency unregistered if the timeout expires.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Bar;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///
+    ///     let registered = manager
+    ///         .register_with_timeout::<Bar>(Some(Lifetime::Singleton), Duration::from_secs(1))
+    ///         .await;
+    ///
+    ///     assert!(registered.is_ok());
+    ///     assert!(manager.has::<DIObj<Bar>>());
+    /// }
+    /// ```
+    pub async fn register_with_timeout<T>(
+        &mut self,
+        lifetime: Option<Lifetime>,
+        duration: Duration,
+    ) -> Result<&mut Self, Elapsed>
+    where
+        T: DIBuilder + 'static,
+    {
+        timeout(duration, self.register::<T>(lifetime)).await
+    }
+
+    /// Resolves a dependency like [`resolve`](DIManager::resolve), giving up if building it takes longer than the given duration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use yadir::core::primitives::{DIManager, DIObj, Lifetime};
+    /// use yadir_derive::DIBuilder;
+    ///
+    /// #[derive(Clone, DIBuilder)]
+    /// struct Bar;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut manager = DIManager::default();
+    ///     manager.register::<Bar>(None).await;
+    ///
+    ///     let bar = manager.resolve_with_timeout::<Bar>(Duration::from_secs(1)).await;
+    ///
+    ///     assert!(bar.unwrap().is_some());
+    /// }
+    /// ```
+    pub async fn resolve_with_timeout<T>(
+        &mut self,
+        duration: Duration,
+    ) -> Result<Option<DIObj<T::Output>>, Elapsed>
+    where
+        T: DIBuilder + 'static,
+    {
+        timeout(duration, self.resolve::<T>()).await
+    }
+}
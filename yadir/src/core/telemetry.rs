@@ -0,0 +1,168 @@
+//! Optional telemetry emitted by the dependency injection manager.
+//!
+//! With the `tracing` feature enabled, every build and resolve is wrapped in a [`tracing`] span, so that builds triggered
+//! while resolving or rebuilding another dependency are nested under it. Every dependency injected into a build gets its own
+//! `inject` span under the `build` span of the dependent, following the dependency tree one level at a time: injected
+//! dependencies are retrieved from the manager rather than built on the spot, so their own `build` spans are the ones recorded
+//! when they were last built. With the `metrics` feature enabled, the following [`metrics`] are recorded:
+//! - `yadir_builds_total` (counter, labeled by `type` and `lifetime`): the number of dependencies built.
+//! - `yadir_build_duration_seconds` (histogram, labeled by `type` and `lifetime`): the time spent building dependencies.
+//! - `yadir_resolves_total` (counter, labeled by `type` and `lifetime`): the number of dependencies resolved.
+//! - `yadir_resolve_failures_total` (counter, labeled by `type` and `error`): the number of failed builds and resolves.
+//!
+//! Without any of these features, all the functions below compile down to no-ops.
+
+use crate::core::primitives::{Lifetime, ResolveError};
+use std::future::Future;
+use std::time::Duration;
+
+#[cfg_attr(not(any(feature = "tracing", feature = "metrics")), allow(dead_code))]
+fn error_label(error: ResolveError) -> &'static str {
+    match error {
+        ResolveError::NotRegistered => "not_registered",
+        ResolveError::MissingDependencies => "missing_dependencies",
+        ResolveError::Vetoed => "vetoed",
+        ResolveError::Failed => "failed",
+    }
+}
+
+/// Wraps the build of a dependency, including the retrieval of its inputs, in a `build` span.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn in_build_span<F>(
+    type_name: &'static str,
+    lifetime: Lifetime,
+    future: F,
+) -> impl Future<Output = F::Output>
+where
+    F: Future,
+{
+    #[cfg(feature = "tracing")]
+    let future = tracing::Instrument::instrument(
+        future,
+        tracing::info_span!("build", type_name, lifetime = %lifetime),
+    );
+
+    future
+}
+
+/// Runs the synchronous build of a dependency, including the retrieval of its inputs, within a `build` span.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn in_build_span_sync<R>(
+    type_name: &'static str,
+    lifetime: Lifetime,
+    build: impl FnOnce() -> R,
+) -> R {
+    #[cfg(feature = "tracing")]
+    let build = || tracing::info_span!("build", type_name, lifetime = %lifetime).in_scope(build);
+
+    build()
+}
+
+/// Wraps the resolution of a dependency in a `resolve` span.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn in_resolve_span<F>(
+    type_name: &'static str,
+    lifetime: Lifetime,
+    future: F,
+) -> impl Future<Output = F::Output>
+where
+    F: Future,
+{
+    #[cfg(feature = "tracing")]
+    let future = tracing::Instrument::instrument(
+        future,
+        tracing::info_span!("resolve", type_name, lifetime = %lifetime),
+    );
+
+    future
+}
+
+/// Runs the synchronous resolution of a dependency within a `resolve` span.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn in_resolve_span_sync<R>(
+    type_name: &'static str,
+    lifetime: Lifetime,
+    resolve: impl FnOnce() -> R,
+) -> R {
+    #[cfg(feature = "tracing")]
+    let resolve =
+        || tracing::info_span!("resolve", type_name, lifetime = %lifetime).in_scope(resolve);
+
+    resolve()
+}
+
+/// Retrieves a dependency injected as input while building another one within an `inject` span, nested under the `build`
+/// span of the latter.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn in_inject_span<R>(
+    type_name: &'static str,
+    retrieve: impl FnOnce() -> Option<R>,
+) -> Option<R> {
+    #[cfg(feature = "tracing")]
+    let retrieve = || {
+        tracing::trace_span!("inject", type_name).in_scope(|| {
+            let obj = retrieve();
+            tracing::trace!(found = obj.is_some(), "injecting {}", type_name);
+
+            obj
+        })
+    };
+
+    retrieve()
+}
+
+/// Records a dependency successfully built in the given amount of time.
+#[cfg_attr(
+    not(any(feature = "tracing", feature = "metrics")),
+    allow(unused_variables)
+)]
+pub(crate) fn record_build(type_name: &'static str, lifetime: Lifetime, elapsed: Duration) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        lifetime = %lifetime,
+        elapsed_ms = elapsed.as_secs_f64() * 1000.0,
+        "built {}",
+        type_name
+    );
+
+    #[cfg(feature = "metrics")]
+    {
+        let lifetime = lifetime.to_string();
+        metrics::counter!("yadir_builds_total", "type" => type_name, "lifetime" => lifetime.clone())
+            .increment(1);
+        metrics::histogram!("yadir_build_duration_seconds", "type" => type_name, "lifetime" => lifetime)
+            .record(elapsed.as_secs_f64());
+    }
+}
+
+/// Records a dependency successfully resolved.
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record_resolve(type_name: &'static str, lifetime: Lifetime) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(
+        "yadir_resolves_total",
+        "type" => type_name,
+        "lifetime" => lifetime.to_string()
+    )
+    .increment(1);
+}
+
+/// Records a failed build or resolve.
+#[cfg_attr(
+    not(any(feature = "tracing", feature = "metrics")),
+    allow(unused_variables)
+)]
+pub(crate) fn record_failure(type_name: &'static str, error: ResolveError) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(
+        type_name,
+        error = error_label(error),
+        "failed to resolve {}: {}",
+        type_name,
+        error
+    );
+
+    #[cfg(feature = "metrics")]
+    metrics::counter!("yadir_resolve_failures_total", "type" => type_name, "error" => error_label(error))
+        .increment(1);
+}
@@ -2,6 +2,9 @@
 //!
 //! `yadir` is yet another simple dependency injection registry for Rust.
 
+// Allows the code generated by `yadir_derive` to refer to `::yadir` from within this crate too.
+extern crate self as yadir;
+
 pub mod core;
 pub mod macros;
 
@@ -9,27 +12,40 @@ pub mod macros;
 extern crate yadir_derive;
 
 #[cfg(feature = "derive")]
-pub use yadir_derive::DIBuilder;
+pub use yadir_derive::{container, injectable, provider, DIBuilder};
+
+// The items the code generated by `yadir_derive` refers to, so that it does not depend on what is imported where it expands.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::core::container::{Acyclic, AcyclicInput, Provides, ResolveInput};
+    pub use crate::core::contracts::{DIBuilder, DIBuilderAs, Provider, SyncDIBuilder};
+    pub use crate::core::introspection::{BuilderMetadata, TypeDescriptor};
+    pub use crate::core::primitives::{DIManager, DIObj, Lifetime, ResolveError};
+    #[cfg(feature = "registry")]
+    pub use crate::core::registry::{inventory, Component};
+}
 
 #[cfg(test)]
 mod tests {
-    use crate::core::contracts::DIBuilder;
-    use crate::core::primitives::{DIManager, DIObj, Lifetime};
-    use crate::{deps, let_deps};
-    use async_trait::async_trait;
+    use crate::core::contracts::{DIBuilder, Interceptor};
+    use crate::core::introspection::{BuilderMetadata, TypeDescriptor};
+    use crate::core::primitives::{
+        DIManager, DIObj, Lifetime, PoisonedError, ResolveError, TypeMap,
+    };
     use claim::assert_some;
-    use dyn_clone::{clone_trait_object, DynClone};
+    use futures::executor::block_on;
+    use std::any::type_name;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
     use uuid::Uuid;
     use yadir_derive::DIBuilder;
 
-    clone_trait_object!(Printer);
-    clone_trait_object!(Writer);
-
-    trait Printer: Sync + Send + DynClone {
+    trait Printer: Sync + Send {
         fn print(&self) -> String;
     }
 
-    trait Writer: Sync + Send + DynClone {
+    trait Writer: Sync + Send {
         fn write(&self) -> String;
     }
 
@@ -43,6 +59,15 @@ mod tests {
         }
     }
 
+    #[derive(Clone)]
+    struct FailoverPrinter;
+
+    impl Printer for FailoverPrinter {
+        fn print(&self) -> String {
+            "failover".to_string()
+        }
+    }
+
     #[derive(Clone, DIBuilder)]
     #[build_as(Box<dyn Writer>)]
     struct Baz;
@@ -57,24 +82,39 @@ mod tests {
     #[build_method("new")]
     struct Foo {
         id: Uuid,
+        greeting: String,
         #[deps]
-        printer: Box<dyn Printer>,
+        printer: DIObj<Box<dyn Printer>>,
         #[deps]
-        writer: Box<dyn Writer>,
+        writer: DIObj<Box<dyn Writer>>,
     }
 
     impl Foo {
-        fn new(printer: Box<dyn Printer>, writer: Box<dyn Writer>) -> Self {
+        fn new(printer: DIObj<Box<dyn Printer>>, writer: DIObj<Box<dyn Writer>>) -> Self {
             let id = Uuid::new_v4();
+            let greeting = format!(
+                "foo {} {}",
+                printer.read().unwrap().print(),
+                writer.read().unwrap().write()
+            );
             Self {
                 id,
+                greeting,
                 printer,
                 writer,
             }
         }
 
         fn print(&self) -> String {
-            format!("foo {} {}", self.printer.print(), self.writer.write())
+            self.greeting.clone()
+        }
+
+        fn print_live(&self) -> String {
+            format!(
+                "foo {} {}",
+                self.printer.read().unwrap().print(),
+                self.writer.read().unwrap().write()
+            )
         }
 
         fn id(&self) -> Uuid {
@@ -82,6 +122,55 @@ mod tests {
         }
     }
 
+    #[derive(Default, DIBuilder)]
+    #[build_method("default")]
+    struct Pool {
+        connections: AtomicUsize,
+    }
+
+    #[derive(DIBuilder)]
+    struct Repository {
+        #[deps]
+        pool: DIObj<Pool>,
+    }
+
+    struct Recorder {
+        name: &'static str,
+        vetoed: Option<&'static str>,
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Recorder {
+        fn record(&self, event: String) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("{} {}", self.name, event));
+        }
+    }
+
+    impl Interceptor for Recorder {
+        fn before_build(&self, metadata: &BuilderMetadata, lifetime: Lifetime) -> bool {
+            self.record(format!("before_build {} {}", metadata.type_name, lifetime));
+            self.vetoed != Some(metadata.type_name)
+        }
+
+        fn after_build(&self, metadata: &BuilderMetadata, lifetime: Lifetime, _: Duration) {
+            self.record(format!("after_build {} {}", metadata.type_name, lifetime));
+        }
+
+        fn on_resolve(&self, metadata: &BuilderMetadata, lifetime: Lifetime) {
+            self.record(format!("on_resolve {} {}", metadata.type_name, lifetime));
+        }
+
+        fn on_resolve_error(&self, metadata: &BuilderMetadata, error: &ResolveError) {
+            self.record(format!(
+                "on_resolve_error {} {:?}",
+                metadata.type_name, error
+            ));
+        }
+    }
+
     #[tokio::test]
     async fn test_di_manager_for_deps_transient_lifetimes() {
         let mut manager = DIManager::default();
@@ -149,4 +238,787 @@ mod tests {
         let foo = manager.resolve::<Foo>().await;
         assert!(foo.is_none());
     }
+
+    #[tokio::test]
+    async fn test_di_manager_for_resolving_by_output_type() {
+        let mut manager = DIManager::default();
+
+        manager
+            .register::<Bar>(Some(Lifetime::Transient))
+            .await
+            .register::<Baz>(Some(Lifetime::Singleton))
+            .await
+            .register::<Foo>(Some(Lifetime::Transient))
+            .await;
+
+        let printer = manager.get::<Box<dyn Printer>>().await;
+        assert_eq!(printer.unwrap().read().unwrap().print(), "bar");
+
+        let foo1 = manager.get::<Foo>().await.unwrap().extract();
+        let foo2 = manager.get_service::<Foo>().await.unwrap().extract();
+        assert_eq!(foo1.print(), "foo bar baz");
+        assert_ne!(foo1.id(), foo2.id());
+
+        assert!(manager.get::<FailoverPrinter>().await.is_none());
+
+        let mut manager = DIManager::default();
+
+        manager
+            .register::<Bar>(Some(Lifetime::Transient))
+            .await
+            .register::<Baz>(Some(Lifetime::Transient))
+            .await
+            .register::<Foo>(Some(Lifetime::Singleton))
+            .await;
+
+        let foo1 = manager.get::<Foo>().await.unwrap().extract();
+        let foo2 = manager.resolve::<Foo>().await.unwrap().extract();
+        assert_eq!(foo1.id(), foo2.id());
+
+        manager
+            .register::<Pool>(Some(Lifetime::Singleton))
+            .await
+            .forward::<Pool, Box<dyn Printer>>(|_| Box::new(FailoverPrinter));
+
+        let printer = manager.get::<Box<dyn Printer>>().await;
+        assert_eq!(printer.unwrap().read().unwrap().print(), "failover");
+    }
+
+    #[tokio::test]
+    async fn test_di_manager_for_replacing_singletons_for_all_holders() {
+        let mut manager = DIManager::default();
+
+        manager
+            .register::<Bar>(Some(Lifetime::Singleton))
+            .await
+            .register::<Baz>(Some(Lifetime::Transient))
+            .await;
+
+        let printer = manager.resolve::<Bar>().await;
+        assert_some!(printer.clone());
+
+        let previous = manager.replace_singleton::<Bar>(Box::new(FailoverPrinter));
+        assert_eq!(previous.unwrap().print(), "bar");
+
+        assert_eq!(printer.unwrap().read().unwrap().print(), "failover");
+        assert_eq!(
+            manager
+                .resolve::<Bar>()
+                .await
+                .unwrap()
+                .read()
+                .unwrap()
+                .print(),
+            "failover"
+        );
+
+        assert!(manager.replace_singleton::<Baz>(Box::new(Baz)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_di_manager_for_replacing_singletons_with_dependents() {
+        let mut manager = DIManager::default();
+
+        manager
+            .register::<Bar>(Some(Lifetime::Singleton))
+            .await
+            .register::<Baz>(Some(Lifetime::Transient))
+            .await
+            .register::<Foo>(Some(Lifetime::Singleton))
+            .await;
+
+        let notifications = Arc::new(AtomicUsize::new(0));
+        let counter = notifications.clone();
+        manager.subscribe::<Foo>(move |foo| {
+            assert_eq!(foo.extract().print(), "foo failover baz");
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let foo = manager.resolve::<Foo>().await.unwrap();
+        assert_eq!(foo.extract().print(), "foo bar baz");
+
+        let previous = manager.replace_singleton::<Bar>(Box::new(FailoverPrinter));
+        assert_some!(previous);
+        assert_eq!(foo.extract().print(), "foo bar baz");
+
+        // hot-swap from a spawned task
+        let manager = tokio::spawn(async move {
+            manager
+                .replace_singleton_with_dependents::<Bar>(Box::new(FailoverPrinter))
+                .await;
+            manager
+        })
+        .await
+        .unwrap();
+        assert!(manager.has::<DIObj<Foo>>());
+        assert_eq!(foo.extract().print(), "foo failover baz");
+        assert_eq!(notifications.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_di_manager_for_exporting_registrations_graph() {
+        let mut manager = DIManager::default();
+
+        manager
+            .register::<Bar>(Some(Lifetime::Singleton))
+            .await
+            .register::<Baz>(Some(Lifetime::Transient))
+            .await
+            .register::<Foo>(Some(Lifetime::Transient))
+            .await;
+
+        let registrations = manager.registrations();
+        let (bar, foo) = (&registrations[0], &registrations[2]);
+        assert_eq!(registrations.len(), 3);
+        assert_eq!(bar.type_name, "Bar");
+        assert_eq!(bar.output.name, "Box<dyn Printer>");
+        assert_eq!(bar.lifetime, Lifetime::Singleton);
+        assert_eq!(foo.dependencies, [bar.output, registrations[1].output]);
+        assert_eq!(bar.key, TypeDescriptor::of::<DIObj<Box<dyn Printer>>>());
+
+        assert!(format!("{manager:?}").starts_with("DIManager { registrations: ["));
+
+        let graph = manager.dependency_graph();
+        assert_eq!(
+            graph.to_dot(),
+            "digraph dependencies {\n    \
+                n0 [label=\"Bar: Box<dyn Printer>\\n(singleton)\"];\n    \
+                n1 [label=\"Baz: Box<dyn Writer>\\n(transient)\"];\n    \
+                n2 [label=\"Foo\\n(transient)\"];\n    \
+                n2 -> n0;\n    \
+                n2 -> n1;\n\
+            }\n"
+        );
+        assert_eq!(
+            graph.to_mermaid(),
+            "flowchart TD\n    \
+                n0[\"Bar: Box#lt;dyn Printer#gt;<br/>(singleton)\"]\n    \
+                n1[\"Baz: Box#lt;dyn Writer#gt;<br/>(transient)\"]\n    \
+                n2[\"Foo<br/>(transient)\"]\n    \
+                n2 --> n0\n    \
+                n2 --> n1\n"
+        );
+        assert_eq!(
+            graph.to_json(),
+            concat!(
+                r#"{"registrations":["#,
+                r#"{"type_name":"Bar","output":"Box<dyn Printer>","lifetime":"singleton","dependencies":[]},"#,
+                r#"{"type_name":"Baz","output":"Box<dyn Writer>","lifetime":"transient","dependencies":[]},"#,
+                r#"{"type_name":"Foo","output":"Foo","lifetime":"transient","dependencies":["Box<dyn Printer>","Box<dyn Writer>"]}"#,
+                r#"]}"#
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_di_manager_for_invoking_interceptors_in_order() {
+        let events = Arc::new(Mutex::new(vec![]));
+        let mut manager = DIManager::default();
+
+        manager
+            .add_interceptor(Recorder {
+                name: "outer",
+                vetoed: None,
+                events: events.clone(),
+            })
+            .add_interceptor(Recorder {
+                name: "inner",
+                vetoed: None,
+                events: events.clone(),
+            })
+            .register::<Bar>(Some(Lifetime::Singleton))
+            .await;
+
+        assert_some!(manager.resolve::<Bar>().await);
+        assert!(manager.resolve::<Baz>().await.is_none());
+        assert!(manager.get::<FailoverPrinter>().await.is_none());
+
+        let failover = type_name::<FailoverPrinter>();
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                "outer before_build Bar singleton",
+                "inner before_build Bar singleton",
+                "inner after_build Bar singleton",
+                "outer after_build Bar singleton",
+                "outer on_resolve Bar singleton",
+                "inner on_resolve Bar singleton",
+                "outer on_resolve_error Baz NotRegistered",
+                "inner on_resolve_error Baz NotRegistered",
+                format!("outer on_resolve_error {} NotRegistered", failover).as_str(),
+                format!("inner on_resolve_error {} NotRegistered", failover).as_str(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_di_manager_for_vetoing_builds_via_interceptors() {
+        let events = Arc::new(Mutex::new(vec![]));
+        let mut manager = DIManager::default();
+
+        manager
+            .add_interceptor(Recorder {
+                name: "veto",
+                vetoed: Some("Baz"),
+                events: events.clone(),
+            })
+            .add_interceptor(Recorder {
+                name: "skipped",
+                vetoed: None,
+                events: events.clone(),
+            })
+            .register::<Baz>(Some(Lifetime::Transient))
+            .await;
+
+        assert!(!manager.has::<DIObj<Box<dyn Writer>>>());
+        assert!(manager.build::<Foo>().await.is_none());
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                "veto before_build Baz transient",
+                "veto on_resolve_error Baz Vetoed",
+                "skipped on_resolve_error Baz Vetoed",
+                "veto on_resolve_error Foo MissingDependencies",
+                "skipped on_resolve_error Foo MissingDependencies",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_di_manager_for_running_under_a_minimal_executor() {
+        let mut manager = DIManager::default();
+
+        block_on(async {
+            manager
+                .register::<Bar>(Some(Lifetime::Singleton))
+                .await
+                .register::<Baz>(Some(Lifetime::Transient))
+                .await
+                .register::<Foo>(Some(Lifetime::Transient))
+                .await;
+        });
+
+        let foo = block_on(manager.resolve::<Foo>());
+        assert_some!(foo.clone());
+        assert_eq!(foo.unwrap().extract().print(), "foo bar baz");
+
+        block_on(manager.replace_singleton_with_dependents::<Bar>(Box::new(FailoverPrinter)));
+        let foo = block_on(manager.resolve::<Foo>()).unwrap();
+        assert_eq!(foo.extract().print(), "foo failover baz");
+    }
+
+    #[tokio::test]
+    async fn test_di_manager_for_registering_generic_builders_from_spawned_tasks() {
+        fn spawn_register<T>(mut manager: DIManager) -> tokio::task::JoinHandle<DIManager>
+        where
+            T: DIBuilder + 'static,
+        {
+            tokio::spawn(async move {
+                manager.register::<T>(Some(Lifetime::Singleton)).await;
+                manager
+            })
+        }
+
+        let manager = spawn_register::<Bar>(DIManager::default()).await.unwrap();
+        assert!(manager.has::<DIObj<Box<dyn Printer>>>());
+    }
+
+    #[tokio::test]
+    async fn test_di_manager_for_guarding_access_to_singletons() {
+        let mut manager = DIManager::default();
+
+        manager
+            .register::<Bar>(Some(Lifetime::Singleton))
+            .await
+            .register::<Baz>(Some(Lifetime::Transient))
+            .await;
+
+        let bar = manager.resolve::<Bar>().await.unwrap();
+        let shared = manager.resolve::<Bar>().await.unwrap();
+        assert!(bar.ptr_eq(&shared));
+
+        let mut printer = bar.write_async().await.unwrap();
+        tokio::task::yield_now().await;
+        *printer = Box::new(FailoverPrinter);
+        drop(printer);
+
+        assert_eq!(
+            shared.with(|printer| printer.print()),
+            Ok("failover".to_string())
+        );
+
+        let baz = manager.resolve::<Baz>().await.unwrap();
+        assert!(!baz.ptr_eq(&manager.resolve::<Baz>().await.unwrap()));
+
+        let weak = baz.downgrade();
+        drop(baz);
+        assert!(weak.upgrade().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_di_manager_for_surfacing_poisoned_singletons() {
+        let mut manager = DIManager::default();
+        manager.register::<Bar>(Some(Lifetime::Singleton)).await;
+
+        let bar = manager.resolve::<Bar>().await.unwrap();
+        let poisoner = bar.clone();
+        let result = std::thread::spawn(move || {
+            poisoner.with_mut(|_| panic!("failed to update the printer"))
+        })
+        .join();
+
+        assert!(result.is_err());
+        assert!(bar.is_poisoned());
+        assert_eq!(bar.with(|printer| printer.print()), Err(PoisonedError));
+
+        manager.replace_singleton::<Bar>(Box::new(FailoverPrinter));
+
+        assert!(!bar.is_poisoned());
+        assert_eq!(
+            bar.with(|printer| printer.print()),
+            Ok("failover".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_di_manager_for_sharing_non_clone_deps() {
+        let mut manager = DIManager::default();
+
+        manager
+            .register::<Pool>(Some(Lifetime::Singleton))
+            .await
+            .register::<Repository>(Some(Lifetime::Transient))
+            .await;
+
+        let first = manager.resolve::<Repository>().await.unwrap();
+        let second = manager.resolve::<Repository>().await.unwrap();
+        assert!(!first.ptr_eq(&second));
+
+        first
+            .with(|repository| {
+                repository
+                    .pool
+                    .with(|pool| pool.connections.fetch_add(1, Ordering::SeqCst))
+            })
+            .unwrap()
+            .unwrap();
+
+        let pool = manager.resolve::<Pool>().await.unwrap();
+        assert!(second
+            .with(|repository| repository.pool.ptr_eq(&pool))
+            .unwrap());
+        assert_eq!(pool.read().unwrap().connections.load(Ordering::SeqCst), 1);
+        assert_eq!(Repository::metadata().dependencies[0].name, "Pool");
+    }
+
+    #[tokio::test]
+    async fn test_di_manager_for_forwarding_deps_to_several_outputs() {
+        trait Connections: Sync + Send {
+            fn open(&self) -> usize;
+        }
+
+        impl Connections for DIObj<Pool> {
+            fn open(&self) -> usize {
+                self.read()
+                    .unwrap()
+                    .connections
+                    .fetch_add(1, Ordering::SeqCst)
+                    + 1
+            }
+        }
+
+        struct PoolStats(DIObj<Pool>);
+
+        #[derive(DIBuilder)]
+        struct Monitor {
+            #[deps]
+            connections: DIObj<Box<dyn Connections>>,
+        }
+
+        #[derive(Debug, PartialEq)]
+        struct FooId(Uuid);
+
+        let mut manager = DIManager::default();
+
+        manager
+            .register::<Pool>(Some(Lifetime::Singleton))
+            .await
+            .forward::<Pool, Box<dyn Connections>>(|pool| Box::new(pool))
+            .forward::<Pool, PoolStats>(PoolStats)
+            .register::<Monitor>(Some(Lifetime::Singleton))
+            .await;
+
+        let connections = manager.get::<Box<dyn Connections>>().await.unwrap();
+        assert_eq!(connections.read().unwrap().open(), 1);
+
+        let monitor = manager.resolve::<Monitor>().await.unwrap();
+        assert_eq!(
+            monitor.read().unwrap().connections.read().unwrap().open(),
+            2
+        );
+
+        let stats = manager.get::<PoolStats>().await.unwrap();
+        let pool = manager.resolve::<Pool>().await.unwrap();
+        assert!(stats.read().unwrap().0.ptr_eq(&pool));
+        assert_eq!(pool.read().unwrap().connections.load(Ordering::SeqCst), 2);
+
+        let registrations = manager.registrations();
+        assert_eq!(registrations[1].type_name, registrations[0].type_name);
+        assert_eq!(registrations[1].lifetime, Lifetime::Singleton);
+        assert_eq!(registrations[1].dependencies, vec![registrations[0].output]);
+
+        manager
+            .register::<Bar>(Some(Lifetime::Transient))
+            .await
+            .register::<Baz>(Some(Lifetime::Transient))
+            .await
+            .register::<Foo>(Some(Lifetime::Transient))
+            .await
+            .forward::<Foo, FooId>(|foo| FooId(foo.read().unwrap().id()));
+
+        let first = manager.get::<FooId>().await.unwrap();
+        let second = manager.get::<FooId>().await.unwrap();
+        assert_ne!(*first.read().unwrap(), *second.read().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_di_manager_for_not_forwarding_unregistered_deps() {
+        let events = Arc::new(Mutex::new(vec![]));
+        let mut manager = DIManager::default();
+
+        manager
+            .add_interceptor(Recorder {
+                name: "recorder",
+                vetoed: None,
+                events: events.clone(),
+            })
+            .forward::<Pool, Box<dyn Printer>>(|_| Box::new(FailoverPrinter));
+
+        assert!(!manager.has::<DIObj<Box<dyn Printer>>>());
+        assert!(manager.registrations().is_empty());
+        assert!(manager.get::<Box<dyn Printer>>().await.is_none());
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                format!(
+                    "recorder on_resolve_error {} NotRegistered",
+                    type_name::<Pool>()
+                ),
+                format!(
+                    "recorder on_resolve_error {} NotRegistered",
+                    type_name::<Box<dyn Printer>>()
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_di_manager_for_injecting_shared_handles() {
+        let mut manager = DIManager::default();
+
+        manager
+            .register::<Bar>(Some(Lifetime::Singleton))
+            .await
+            .register::<Baz>(Some(Lifetime::Transient))
+            .await
+            .register::<Foo>(Some(Lifetime::Transient))
+            .await;
+
+        let foo = manager.resolve::<Foo>().await.unwrap().extract();
+        manager.replace_singleton::<Bar>(Box::new(FailoverPrinter));
+
+        assert_eq!(foo.print(), "foo bar baz");
+        assert_eq!(foo.print_live(), "foo failover baz");
+    }
+
+    #[test]
+    fn test_type_map_as_standalone_extensions_map() {
+        let mut map = TypeMap::default();
+        assert!(map.is_empty());
+
+        map.insert(42u32);
+        map.insert(String::from("config"));
+        map.entry::<Vec<u8>>().or_default().push(1);
+        map.entry::<Vec<u8>>().and_modify(|bytes| bytes.push(2));
+        assert_eq!(map.len(), 3);
+
+        let mut names = map.type_names().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(
+            names,
+            ["alloc::string::String", "alloc::vec::Vec<u8>", "u32"]
+        );
+
+        let snapshot = map.clone();
+        assert_eq!(map.remove::<u32>(), Some(42));
+        assert_eq!(
+            map.insert(String::from("updated")),
+            Some(String::from("config"))
+        );
+
+        let shared = Arc::new(snapshot);
+        let reader = shared.clone();
+        let port = std::thread::spawn(move || reader.get::<u32>().copied())
+            .join()
+            .unwrap();
+
+        assert_eq!(port, Some(42));
+        assert_eq!(shared.get::<String>().map(String::as_str), Some("config"));
+        assert_eq!(shared.get::<Vec<u8>>(), Some(&vec![1, 2]));
+
+        map.clear();
+        assert!(map.is_empty());
+        assert!(!map.has::<Vec<u8>>());
+    }
+
+    #[tokio::test]
+    async fn test_di_manager_for_flat_and_nested_inputs() {
+        #[derive(Clone, DIBuilder)]
+        struct Port(#[di(default = 8080)] u16);
+
+        #[derive(Clone, DIBuilder)]
+        struct Host(#[di(default = "localhost".to_string())] String);
+
+        #[derive(Clone, DIBuilder)]
+        struct Scheme(#[di(default = "http")] &'static str);
+
+        struct Flat(String);
+
+        impl DIBuilder for Flat {
+            type Input = crate::deps!(Scheme, Host, Port);
+            type Output = Self;
+
+            async fn build(input: Self::Input) -> Self::Output {
+                crate::let_deps!(scheme, mut host: Host, port: Port <- input);
+                host.0.push_str(".test");
+
+                Self(format!("{}://{}:{}", scheme.0, host.0, port.0))
+            }
+        }
+
+        struct Nested(String);
+
+        impl DIBuilder for Nested {
+            type Input = (DIObj<Scheme>, (DIObj<Host>, (DIObj<Port>, ())));
+            type Output = Self;
+
+            async fn build((scheme, (host, (port, ()))): Self::Input) -> Self::Output {
+                let (scheme, host, port) = (scheme.extract(), host.extract(), port.extract());
+
+                Self(format!("{}://{}:{}", scheme.0, host.0, port.0))
+            }
+        }
+
+        struct NestedBound(String);
+
+        impl DIBuilder for NestedBound {
+            type Input = (DIObj<Scheme>, (DIObj<Host>, (DIObj<Port>, ())));
+            type Output = Self;
+
+            async fn build(input: Self::Input) -> Self::Output {
+                crate::let_deps!(scheme, host, mut port: Port <- input);
+                port.0 += 1;
+
+                Self(format!("{}://{}:{}", scheme.0, host.0, port.0))
+            }
+        }
+
+        let mut manager = DIManager::default();
+        manager
+            .register::<Scheme>(None)
+            .await
+            .register::<Host>(None)
+            .await
+            .register::<Port>(None)
+            .await
+            .register::<Flat>(None)
+            .await
+            .register::<Nested>(None)
+            .await
+            .register::<NestedBound>(None)
+            .await;
+
+        let flat = manager.resolve::<Flat>().await.unwrap();
+        let nested = manager.resolve::<Nested>().await.unwrap();
+        let nested_bound = manager.resolve::<NestedBound>().await.unwrap();
+        assert_eq!(flat.read().unwrap().0, "http://localhost.test:8080");
+        assert_eq!(nested.read().unwrap().0, "http://localhost:8080");
+        assert_eq!(nested_bound.read().unwrap().0, "http://localhost:8081");
+
+        let dependencies = |metadata: BuilderMetadata| {
+            metadata
+                .dependencies
+                .iter()
+                .map(|dependency| dependency.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            dependencies(Flat::metadata()),
+            dependencies(Nested::metadata())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_di_manager_for_generic_let_deps_annotations() {
+        use std::collections::HashMap;
+
+        struct Routes;
+
+        impl DIBuilder for Routes {
+            type Input = crate::deps!();
+            type Output = HashMap<String, u8>;
+
+            async fn build(_: Self::Input) -> Self::Output {
+                HashMap::from([("/".to_string(), 0)])
+            }
+        }
+
+        struct Matrix;
+
+        impl DIBuilder for Matrix {
+            type Input = crate::deps!();
+            type Output = Vec<Vec<u8>>;
+
+            async fn build(_: Self::Input) -> Self::Output {
+                vec![vec![1, 2], vec![3]]
+            }
+        }
+
+        struct Router(usize, usize);
+
+        impl DIBuilder for Router {
+            type Input = crate::deps!(HashMap<String, u8>, Vec<Vec<u8>>);
+            type Output = Self;
+
+            async fn build(input: Self::Input) -> Self::Output {
+                crate::let_deps!(mut routes: HashMap<String, u8>, matrix: Vec<Vec<u8>> <- input);
+                routes.insert("/health".to_string(), 1);
+
+                Self(routes.len(), matrix.concat().len())
+            }
+        }
+
+        let mut manager = DIManager::default();
+        manager
+            .register::<Routes>(None)
+            .await
+            .register::<Matrix>(None)
+            .await
+            .register::<Router>(None)
+            .await;
+
+        let router = manager.resolve::<Router>().await.unwrap();
+        assert_eq!((router.read().unwrap().0, router.read().unwrap().1), (2, 3));
+    }
+
+    #[tokio::test]
+    async fn test_di_manager_for_more_than_sixteen_deps() {
+        macro_rules! numbers {
+            ($( $name:ident = $value:literal ),+) => {
+                $(
+                    #[derive(Clone, DIBuilder)]
+                    struct $name(#[di(default = $value)] u32);
+                )+
+            };
+        }
+
+        numbers!(
+            N1 = 1,
+            N2 = 2,
+            N3 = 3,
+            N4 = 4,
+            N5 = 5,
+            N6 = 6,
+            N7 = 7,
+            N8 = 8,
+            N9 = 9,
+            N10 = 10,
+            N11 = 11,
+            N12 = 12,
+            N13 = 13,
+            N14 = 14,
+            N15 = 15,
+            N16 = 16,
+            N17 = 17
+        );
+
+        struct Sum(u32);
+
+        impl DIBuilder for Sum {
+            type Input = crate::deps!(
+                N1, N2, N3, N4, N5, N6, N7, N8, N9, N10, N11, N12, N13, N14, N15, N16, N17
+            );
+            type Output = Self;
+
+            async fn build(input: Self::Input) -> Self::Output {
+                crate::let_deps!(
+                    n1, n2, n3, n4, n5, n6, n7, n8, n9, n10, n11, n12, n13, n14, n15, n16, n17 <- input
+                );
+
+                Self(
+                    n1.0 + n2.0
+                        + n3.0
+                        + n4.0
+                        + n5.0
+                        + n6.0
+                        + n7.0
+                        + n8.0
+                        + n9.0
+                        + n10.0
+                        + n11.0
+                        + n12.0
+                        + n13.0
+                        + n14.0
+                        + n15.0
+                        + n16.0
+                        + n17.0,
+                )
+            }
+        }
+
+        let mut manager = DIManager::default();
+        manager
+            .register::<N1>(None)
+            .await
+            .register::<N2>(None)
+            .await
+            .register::<N3>(None)
+            .await
+            .register::<N4>(None)
+            .await
+            .register::<N5>(None)
+            .await
+            .register::<N6>(None)
+            .await
+            .register::<N7>(None)
+            .await
+            .register::<N8>(None)
+            .await
+            .register::<N9>(None)
+            .await
+            .register::<N10>(None)
+            .await
+            .register::<N11>(None)
+            .await
+            .register::<N12>(None)
+            .await
+            .register::<N13>(None)
+            .await
+            .register::<N14>(None)
+            .await
+            .register::<N15>(None)
+            .await
+            .register::<N16>(None)
+            .await
+            .register::<N17>(None)
+            .await
+            .register::<Sum>(None)
+            .await;
+
+        let sum = manager.resolve::<Sum>().await.unwrap();
+        assert_eq!(sum.read().unwrap().0, 153);
+        assert_eq!(Sum::metadata().dependencies.len(), 17);
+    }
 }
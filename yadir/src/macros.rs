@@ -1,4 +1,6 @@
-/// A helper macro used to define dependencies for a given type as nested tuples of [`DIObj`](super::core::primitives::DIObj).
+use crate::core::primitives::DIObj;
+
+/// A helper macro used to define dependencies for a given type as a flat tuple of [`DIObj`](super::core::primitives::DIObj).
 ///
 /// First of all, you need to define the dependencies for a given type using the `deps!` macro:
 ///
@@ -6,27 +8,37 @@
 /// deps!(Bar, Baz, Qux);
 /// ```
 ///
-/// Next, it will expand into a nested tuple of [`DIObj`](super::core::primitives::DIObj) for each dependency:
+/// Next, it will expand into a flat tuple of [`DIObj`](super::core::primitives::DIObj) for each dependency:
 ///
 /// ```ignore
-/// (DIObj<Bar>, (DIObj<Baz>, (DIObj<Qux>, ())))
+/// (DIObj<Bar>, DIObj<Baz>, DIObj<Qux>)
 /// ```
+///
+/// Flat tuples implement [`GetInput`](super::core::contracts::GetInput) for up to 16 dependencies, hence more dependencies
+/// expand into nested pairs such as `(DIObj<Bar>, (DIObj<Baz>, (DIObj<Qux>, ())))` instead, which can also be written by hand.
 #[macro_export]
 macro_rules! deps {
-    () => {
+    (@nested) => {
         ()
     };
-    ($t:ty) => {
-        (DIObj<$t>, ())
+    (@nested $t:ty $(, $rest:ty )*) => {
+        ($crate::core::primitives::DIObj<$t>, $crate::deps!(@nested $( $rest ),*))
     };
-    ($t:ty, $( $rest:ty ),*) => {
-        (DIObj<$t>, deps!($( $rest ),*))
+    (
+        $t1:ty, $t2:ty, $t3:ty, $t4:ty, $t5:ty, $t6:ty, $t7:ty, $t8:ty, $t9:ty, $t10:ty, $t11:ty, $t12:ty, $t13:ty, $t14:ty,
+        $t15:ty, $t16:ty, $t17:ty $(, $rest:ty )* $(,)?
+    ) => {
+        $crate::deps!(
+            @nested $t1, $t2, $t3, $t4, $t5, $t6, $t7, $t8, $t9, $t10, $t11, $t12, $t13, $t14, $t15, $t16, $t17
+            $(, $rest )*
+        )
+    };
+    ($( $t:ty ),* $(,)?) => {
+        ($( $crate::core::primitives::DIObj<$t>, )*)
     };
 }
 
-/// A helper macro used to match dependencies for a given type as nested tuples of [`DIObj`](super::core::primitives::DIObj).
-///
-/// The `deps_match!` macro is used to pattern match the dependencies for a given type as nested tuples of [`DIObj`](super::core::primitives::DIObj).
+/// A helper macro used to bind the dependencies defined via [`deps!`](deps) to local variables, extracting each one of them.
 ///
 /// For example, for the following specified dependencies:
 ///
@@ -34,20 +46,127 @@ macro_rules! deps {
 /// type Input = deps!(Bar, Baz, Qux);
 /// ```
 ///
-/// You can use the `deps_match!` macro to pattern match the dependencies as follows:
+/// You can use the `let_deps!` macro to bind the dependencies as follows, where each binding can be declared `mut`
+/// and annotated with its type:
 ///
 /// ```ignore
 /// let input: Input;
-/// let_deps!(bar, baz, qux <- input);
+/// let_deps!(bar, mut baz, qux: Qux <- input);
 /// ```
+///
+/// Both flat tuples and nested pairs of dependencies are supported, as they are split one dependency at a time.
 #[macro_export]
 macro_rules! let_deps {
-    ($name:ident <- $input:ident) => {
-        let $name = $input.0.extract();
+    // split the bindings on the commas outside of generic arguments, whose depth is tracked by a marker per `<`, until the input is reached
+    (@munch [$( $done:tt )*] [$( $current:tt )*] [] , $( $rest:tt )+) => {
+        $crate::let_deps!(@munch [$( $done )* [$( $current )*]] [] [] $( $rest )+)
+    };
+    (@munch [$( $done:tt )*] [$( $current:tt )*] [] <- $input:ident) => {
+        $crate::let_deps!(@bind $input $( $done )* [$( $current )*])
+    };
+    (@munch [$( $done:tt )*] [$( $current:tt )*] [$( $depth:tt )*] < $( $rest:tt )*) => {
+        $crate::let_deps!(@munch [$( $done )*] [$( $current )* <] [< $( $depth )*] $( $rest )*)
+    };
+    (@munch [$( $done:tt )*] [$( $current:tt )*] [< $( $depth:tt )*] > $( $rest:tt )*) => {
+        $crate::let_deps!(@munch [$( $done )*] [$( $current )* >] [$( $depth )*] $( $rest )*)
+    };
+    (@munch [$( $done:tt )*] [$( $current:tt )*] [< < $( $depth:tt )*] >> $( $rest:tt )*) => {
+        $crate::let_deps!(@munch [$( $done )*] [$( $current )* >>] [$( $depth )*] $( $rest )*)
+    };
+    (@munch [$( $done:tt )*] [$( $current:tt )*] [$( $depth:tt )*] $next:tt $( $rest:tt )*) => {
+        $crate::let_deps!(@munch [$( $done )*] [$( $current )* $next] [$( $depth )*] $( $rest )*)
+    };
+    (@bind $input:ident $binding:tt) => {
+        let ($crate::let_deps!(@name $binding), _) = $crate::macros::SplitDeps::split($input);
+        $crate::let_deps!(@extract $binding);
+    };
+    (@bind $input:ident $binding:tt $( $rest:tt )+) => {
+        let ($crate::let_deps!(@name $binding), $input) = $crate::macros::SplitDeps::split($input);
+        $crate::let_deps!(@extract $binding);
+        $crate::let_deps!(@bind $input $( $rest )+);
+    };
+    (@name [mut $name:ident $( $rest:tt )*]) => {
+        $name
+    };
+    (@name [$name:ident $( $rest:tt )*]) => {
+        $name
+    };
+    (@extract [mut $name:ident : $t:ty]) => {
+        let mut $name: $t = $name.extract();
+    };
+    (@extract [mut $name:ident]) => {
+        let mut $name = $name.extract();
     };
-    ($name:ident, $( $rest:ident ),* <- $input:ident) => {
-        let $name = $input.0.extract();
-        let $input = $input.1;
-        let_deps!($( $rest ),* <- $input);
+    (@extract [$name:ident : $t:ty]) => {
+        let $name: $t = $name.extract();
     };
+    (@extract [$name:ident]) => {
+        let $name = $name.extract();
+    };
+    ($( $bindings:tt )+) => {
+        $crate::let_deps!(@munch [] [] [] $( $bindings )+)
+    };
+}
+
+/// Splits the first dependency off an input defined via [`deps!`](deps), be it a flat tuple or nested pairs, see [`let_deps!`](let_deps).
+#[doc(hidden)]
+pub trait SplitDeps {
+    type Head;
+    type Tail;
+
+    fn split(self) -> (Self::Head, Self::Tail);
+}
+
+impl<T> SplitDeps for (DIObj<T>, ()) {
+    type Head = DIObj<T>;
+    type Tail = ();
+
+    fn split(self) -> (Self::Head, Self::Tail) {
+        self
+    }
 }
+
+impl<T, S, R> SplitDeps for (DIObj<T>, (S, R)) {
+    type Head = DIObj<T>;
+    type Tail = (S, R);
+
+    fn split(self) -> (Self::Head, Self::Tail) {
+        self
+    }
+}
+
+macro_rules! impl_split_deps_for_tuples {
+    ($( ($( $t:ident ),*) ),+ $(,)?) => {
+        $(
+            impl<Head, $( $t ),*> SplitDeps for (DIObj<Head>, $( DIObj<$t>, )*) {
+                type Head = DIObj<Head>;
+                type Tail = ($( DIObj<$t>, )*);
+
+                #[allow(non_snake_case)]
+                fn split(self) -> (Self::Head, Self::Tail) {
+                    let (head, $( $t, )*) = self;
+                    (head, ($( $t, )*))
+                }
+            }
+        )+
+    };
+}
+
+impl_split_deps_for_tuples!(
+    (),
+    (B),
+    (B, C),
+    (B, C, D),
+    (B, C, D, E),
+    (B, C, D, E, F),
+    (B, C, D, E, F, G),
+    (B, C, D, E, F, G, H),
+    (B, C, D, E, F, G, H, I),
+    (B, C, D, E, F, G, H, I, J),
+    (B, C, D, E, F, G, H, I, J, K),
+    (B, C, D, E, F, G, H, I, J, K, L),
+    (B, C, D, E, F, G, H, I, J, K, L, M),
+    (B, C, D, E, F, G, H, I, J, K, L, M, N),
+    (B, C, D, E, F, G, H, I, J, K, L, M, N, O),
+    (B, C, D, E, F, G, H, I, J, K, L, M, N, O, P),
+);
@@ -1,20 +1,144 @@
 use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{Attribute, ItemStruct, LitStr};
+use syn::{Attribute, ItemStruct, Token};
 
-use crate::helper_primitives::{BuildMethod, StructField, TypeOutput};
+use crate::helper_primitives::{
+    input_tuple, lifetime_variant, ok_type, shared_handle_type, spelled_type_name, BuildMethod,
+    Constructor, ContainerAttributes, StaticContainer, StructField, TypeOutput,
+};
 
-fn get_build_as_attr(attr: &Attribute) -> syn::Result<TypeOutput> {
-    let attr_type = attr.parse_args::<syn::Type>();
+fn get_build_as_attr(attr: &Attribute) -> syn::Result<Vec<TypeOutput>> {
+    let missing_output = || {
+        syn::Error::new_spanned(
+            attr,
+            "Missing output type, expected e.g. #[build_as(Box<dyn Trait>)]",
+        )
+    };
+    let syn::Meta::List(list) = &attr.meta else {
+        return Err(missing_output());
+    };
 
-    match attr_type {
-        Ok(ty) => Ok(TypeOutput::BoxedTraitObjectType(ty)),
-        Err(_) => Ok(TypeOutput::SelfType),
+    let types = list
+        .parse_args_with(Punctuated::<syn::Type, Token![,]>::parse_terminated)
+        .map_err(|e| {
+            syn::Error::new(
+                e.span(),
+                format!("Invalid #[build_as] output type: {e}, expected e.g. #[build_as(Box<dyn Trait>)]"),
+            )
+        })?;
+
+    match types.is_empty() {
+        true => Err(missing_output()),
+        false => types.into_iter().map(TypeOutput::new).collect(),
     }
 }
 
 fn get_build_method_attr(attr: &Attribute) -> syn::Result<BuildMethod> {
-    attr.parse_args::<LitStr>()?.value().try_into()
+    match &attr.meta {
+        syn::Meta::List(list) if !list.tokens.is_empty() => list.parse_args::<BuildMethod>(),
+        _ => Err(syn::Error::new_spanned(
+            attr,
+            "Missing build method, expected e.g. #[build_method(\"new\")] or #[build_method(Self::new)]",
+        )),
+    }
+}
+
+/// Rejects the struct-level attributes used on fields and the field-level ones used on the struct.
+fn check_attr_placement(input: &ItemStruct) -> syn::Result<()> {
+    if let Some(attr) = input.attrs.iter().find(|attr| attr.path().is_ident("deps")) {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "#[deps] can only be used on fields, to mark them as input dependencies",
+        ));
+    }
+
+    let misplaced = input
+        .fields
+        .iter()
+        .flat_map(|field| &field.attrs)
+        .find(|attr| attr.path().is_ident("build_as") || attr.path().is_ident("build_method"));
+
+    match misplaced {
+        Some(attr) => Err(syn::Error::new_spanned(
+            attr,
+            format!(
+                "#[{}] can only be used on the struct, not on its fields",
+                attr.path().to_token_stream()
+            ),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Converts the derive input into a struct, as enums and unions are not supported.
+pub(crate) fn struct_item(input: syn::DeriveInput) -> syn::Result<ItemStruct> {
+    match input.data {
+        syn::Data::Struct(data) => Ok(ItemStruct {
+            attrs: input.attrs,
+            vis: input.vis,
+            struct_token: data.struct_token,
+            ident: input.ident,
+            generics: input.generics,
+            fields: data.fields,
+            semi_token: data.semi_token,
+        }),
+        syn::Data::Enum(data) => Err(syn::Error::new_spanned(
+            data.enum_token,
+            "DIBuilder can only be derived for structs, not for enums",
+        )),
+        syn::Data::Union(data) => Err(syn::Error::new_spanned(
+            data.union_token,
+            "DIBuilder can only be derived for structs, not for unions",
+        )),
+    }
+}
+
+/// Orders the bound #[deps] fields as declared via `args(...)`, or as declared in the struct otherwise.
+fn constructor_args<'a>(
+    constructor: &Constructor,
+    field_members: &[syn::Member],
+    field_idents: &[&'a syn::Ident],
+) -> syn::Result<Vec<&'a syn::Ident>> {
+    let Some(args) = &constructor.args else {
+        return Ok(field_idents.to_vec());
+    };
+
+    let mut ordered = Vec::with_capacity(args.len());
+    for arg in args {
+        let Some(position) = field_members.iter().position(|member| member == arg) else {
+            return Err(syn::Error::new_spanned(
+                arg,
+                "Only #[deps] fields can be passed to the build method",
+            ));
+        };
+
+        if ordered.contains(&field_idents[position]) {
+            return Err(syn::Error::new_spanned(
+                arg,
+                "The #[deps] field is passed more than once to the build method",
+            ));
+        }
+
+        ordered.push(field_idents[position]);
+    }
+
+    if let Some(missing) = field_members
+        .iter()
+        .zip(field_idents)
+        .find(|(_, ident)| !ordered.contains(ident))
+    {
+        return Err(syn::Error::new_spanned(
+            args,
+            format!(
+                "Missing #[deps] field `{}` in the arguments of the build method",
+                missing.0.to_token_stream()
+            ),
+        ));
+    }
+
+    Ok(ordered)
 }
 
 pub(crate) fn expand_di_builder(input: ItemStruct) -> syn::Result<TokenStream> {
@@ -26,13 +150,19 @@ pub(crate) fn expand_di_builder(input: ItemStruct) -> syn::Result<TokenStream> {
     // - #[build_as] can only be used once and always before #[build_method]
     // - #[build_method] can only be used once and always after #[build_as]
 
+    check_attr_placement(&input)?;
+
+    // get the #[di(...)] attributes of the struct
+    let container_attrs = ContainerAttributes::new(&input.attrs)?;
+    let krate = container_attrs.crate_path();
+
     // get the #[build_as] and #[build_method] attributes according to the rules
-    let (mut build_as_output, mut build_method) = (TypeOutput::SelfType, BuildMethod::None);
+    let (mut build_as_outputs, mut build_method) = (vec![], BuildMethod::None);
     let (mut build_as_output_encountered, mut build_method_encountered) = (0, 0);
 
     for attr in &input.attrs {
         if attr.path().is_ident("build_as") {
-            build_as_output = get_build_as_attr(attr)?;
+            build_as_outputs = get_build_as_attr(attr)?;
             build_as_output_encountered += 1;
 
             if build_as_output_encountered > 1 {
@@ -61,26 +191,48 @@ pub(crate) fn expand_di_builder(input: ItemStruct) -> syn::Result<TokenStream> {
         }
     }
 
-    // get the types of all fields which are annotated with #[di_build]
-    let field_types = match &input.fields {
+    // the first #[build_as] output is the main one, while the others are registered along with it
+    let mut build_as_outputs = build_as_outputs.into_iter();
+    let build_as_output = build_as_outputs.next().unwrap_or(TypeOutput::SelfType);
+    let alias_outputs = build_as_outputs.collect::<Vec<_>>();
+
+    // get the options of all fields which are annotated with #[deps], along with the types they are resolved from
+    let deps_options = match &input.fields {
         syn::Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
         syn::Fields::Unnamed(fields) => fields.unnamed.iter().collect::<Vec<_>>(),
         syn::Fields::Unit => vec![],
     }
-    .iter()
+    .into_iter()
     .map(|field| {
-        let ty = &field.ty;
-
-        match StructField::new(field).is_deps() {
-            Ok(true) => Ok(Some(ty)),
-            Ok(false) => Ok(None),
-            Err(e) => Err(e),
-        }
+        StructField::new(field)
+            .deps_options()
+            .map(|options| options.map(|options| (&field.ty, options)))
     })
     .collect::<syn::Result<Vec<_>>>()?
     .into_iter()
     .flatten()
     .collect::<Vec<_>>();
+    let source_types = deps_options
+        .iter()
+        .map(|(ty, options)| options.from.as_ref().unwrap_or(ty))
+        .collect::<Vec<_>>();
+
+    // `DIObj<T>` sources depend on `T` and receive the shared handle, while the other sources receive a clone of the dependency
+    let field_types = source_types
+        .iter()
+        .map(|ty| shared_handle_type(ty).unwrap_or(ty))
+        .collect::<Vec<_>>();
+    let shared_fields = source_types
+        .iter()
+        .map(|ty| shared_handle_type(ty).is_some())
+        .collect::<Vec<_>>();
+
+    // spell out the input as `deps!` would expand it, so that the derive does not depend on the macro being imported
+    let input_type = input_tuple(
+        field_types
+            .iter()
+            .map(|ty| quote::quote! { #krate::__private::DIObj<#ty> }),
+    );
 
     let named_field_idents = match &input.fields {
         syn::Fields::Named(fields) => fields
@@ -119,109 +271,903 @@ pub(crate) fn expand_di_builder(input: ItemStruct) -> syn::Result<TokenStream> {
         _ => vec![],
     };
 
-    // construct the instantiation of the input struct based on the #[deps] fields and the #[build_method] attribute
-    let build_method = match (
-        build_method,
-        named_field_idents.is_empty(),
-        unnamed_field_idents.is_empty(),
-    ) {
-        (BuildMethod::None, true, true) => quote::quote! {
-            Self
-        },
-        (BuildMethod::None, false, true) => quote::quote! {
-            let_deps!(#(#named_field_idents),* <- input);
+    // the #[deps] fields, along with the names they are bound to while building the struct
+    let field_idents = match named_field_idents.is_empty() {
+        true => unnamed_field_idents.iter().collect::<Vec<_>>(),
+        false => named_field_idents.to_vec(),
+    };
+    let field_members = match &input.fields {
+        syn::Fields::Named(fields) => fields
+            .named
+            .iter()
+            .filter(|field| matches!(StructField::new(field).is_deps(), Ok(true)))
+            .map(|field| syn::Member::Named(field.ident.clone().unwrap()))
+            .collect::<Vec<_>>(),
+        syn::Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| matches!(StructField::new(field).is_deps(), Ok(true)))
+            .map(|(i, _)| syn::Member::Unnamed(syn::Index::from(i)))
+            .collect::<Vec<_>>(),
+        syn::Fields::Unit => vec![],
+    };
+
+    // bind the #[deps] fields by destructuring the input, extracting the ones which are not shared handles and mapping them into the fields
+    let bind_deps = {
+        let pattern = input_tuple(field_idents.iter().map(|ident| ident.to_token_stream()));
+        let extracted_idents = field_idents
+            .iter()
+            .zip(&shared_fields)
+            .filter(|(_, shared)| !**shared)
+            .map(|(ident, _)| ident);
+        let mappings = field_idents
+            .iter()
+            .zip(&deps_options)
+            .filter_map(|(ident, (_, options))| options.map(ident));
+
+        match field_idents.is_empty() {
+            true => quote::quote! {},
+            false => quote::quote! {
+                let #pattern = input;
+                #(
+                    let #extracted_idents = #extracted_idents.extract();
+                )*
+                #(
+                    #mappings
+                )*
+            },
+        }
+    };
+
+    // initialize every field of the input struct, injecting the #[deps] fields and defaulting the others
+    let field_inits = match &input.fields {
+        syn::Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+        syn::Fields::Unnamed(fields) => fields.unnamed.iter().collect::<Vec<_>>(),
+        syn::Fields::Unit => vec![],
+    }
+    .into_iter()
+    .enumerate()
+    .map(|(i, field)| {
+        let struct_field = StructField::new(field);
+        let default_expr = struct_field.default_expr()?;
+        let value = match struct_field.is_deps()? {
+            true => match &field.ident {
+                Some(ident) => quote::quote! { #ident },
+                None => syn::Ident::new(&format!("field_{}", i), field.span()).to_token_stream(),
+            },
+            false => match default_expr {
+                Some(expr) => quote::quote! { #expr },
+                None => quote::quote! { ::std::default::Default::default() },
+            },
+        };
+
+        Ok(match &field.ident {
+            Some(ident) => quote::quote! { #ident: #value },
+            None => value,
+        })
+    })
+    .collect::<syn::Result<Vec<_>>>()?;
+    let hybrid = match &input.fields {
+        syn::Fields::Named(_) => quote::quote! {
+            #bind_deps
 
             Self {
                 #(
-                    #named_field_idents
+                    #field_inits
                 ),*
             }
         },
-        (BuildMethod::None, true, false) => quote::quote! {
-            let_deps!(#(#unnamed_field_idents),* <- input);
+        syn::Fields::Unnamed(_) => quote::quote! {
+            #bind_deps
 
             Self(
                 #(
-                    #unnamed_field_idents
+                    #field_inits
                 ),*
             )
         },
-        (BuildMethod::New, true, true) => quote::quote! {
-            Self::new()
+        syn::Fields::Unit => quote::quote! {
+            Self
         },
-        (BuildMethod::New, false, true) => quote::quote! {
-            let_deps!(#(#named_field_idents),* <- input);
+    };
 
-            Self::new(
-                #(
-                    #named_field_idents
-                ),*
-            )
-        },
-        (BuildMethod::New, true, false) => quote::quote! {
-            let_deps!(#(#unnamed_field_idents),* <- input);
+    // construct the instantiation of the input struct based on the #[deps] fields and the #[build_method] attribute
+    let build_method = match build_method {
+        BuildMethod::None => hybrid,
+        BuildMethod::Constructor(constructor) => {
+            if constructor.is_async && container_attrs.sync {
+                return Err(syn::Error::new_spanned(
+                    &constructor.path,
+                    "Async constructors are not supported by #[di(sync)] builders",
+                ));
+            }
+
+            let args = constructor_args(&constructor, &field_members, &field_idents)?;
+            let path = &constructor.path;
+            let call = match constructor.is_async {
+                true => quote::quote! { #path(#(#args),*).await },
+                false => quote::quote! { #path(#(#args),*) },
+            };
 
-            Self::new(
-                #(
-                    #unnamed_field_idents
-                ),*
-            )
-        },
-        (BuildMethod::Default, false, true)
-        | (BuildMethod::Default, true, false)
-        | (BuildMethod::Default, true, true) => {
             quote::quote! {
-                Self::default()
+                #bind_deps
+
+                #call
             }
         }
-        _ => Err(syn::Error::new_spanned(
-            input.clone(),
-            "Cannot mix named and unnamed fields with #[deps]",
-        ))?,
-    };
-
-    // box the build method output if the #[build_as] attribute is present
-    let build_method = match build_as_output {
-        TypeOutput::SelfType => build_method,
-        TypeOutput::BoxedTraitObjectType(_) => quote::quote! {
-            Box::new(#build_method)
+        // the struct itself only has to implement `Default` when none of its fields are injected
+        BuildMethod::Default => match field_idents.is_empty() {
+            true => quote::quote! {
+                <Self as ::core::default::Default>::default()
+            },
+            false => hybrid,
         },
     };
 
+    // convert the built struct into the main output
+    let construct = build_method;
+    let build_method = build_as_output.wrap(&construct);
+
     // get the name of the input struct
     let input_ident = &input.ident;
 
-    let output = match (
-        named_field_idents.is_empty(),
-        unnamed_field_idents.is_empty(),
-    ) {
-        (true, true) => quote::quote! {
-            #[async_trait]
-            impl DIBuilder for #input_ident {
-                type Input = deps!();
+    // carry the generic parameters of the input struct into the impls, bounded as required by the builder traits
+    if let Some(lifetime) = input.generics.lifetimes().next() {
+        return Err(syn::Error::new_spanned(
+            lifetime,
+            "Lifetime parameters are not supported, as dependencies must be 'static",
+        ));
+    }
+
+    let is_generic = !input.generics.params.is_empty();
+    let mut generics = input.generics.clone();
+    if is_generic {
+        let type_params = input.generics.type_params().map(|param| &param.ident);
+        let extracted_types = field_types
+            .iter()
+            .zip(&shared_fields)
+            .filter(|(_, shared)| !**shared)
+            .map(|(ty, _)| ty);
+        let where_clause = generics.make_where_clause();
+
+        where_clause
+            .predicates
+            .extend(type_params.map(|param| -> syn::WherePredicate {
+                syn::parse_quote! { #param: ::std::marker::Send + ::std::marker::Sync + 'static }
+            }));
+        where_clause
+            .predicates
+            .extend(extracted_types.map(|ty| -> syn::WherePredicate {
+                syn::parse_quote! { #ty: ::std::clone::Clone }
+            }));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // describe the builder using the type names as written in the struct definition, or as instantiated if it is generic
+    let describe_type = |ty: &dyn ToTokens, name: String| match is_generic {
+        true => quote::quote! { #krate::__private::TypeDescriptor::of::<#ty>() },
+        false => {
+            quote::quote! { #krate::__private::TypeDescriptor::named::<#ty>(#name) }
+        }
+    };
+    let input_name = match is_generic {
+        true => quote::quote! { ::std::any::type_name::<Self>() },
+        false => {
+            let input_name = input_ident.to_string();
+            quote::quote! { #input_name }
+        }
+    };
+    let field_descriptors = field_types
+        .iter()
+        .map(|ty| describe_type(ty, spelled_type_name(ty)))
+        .collect::<Vec<_>>();
+    let describe = |output: &TypeOutput| {
+        let output_descriptor = match output {
+            TypeOutput::SelfType => describe_type(output, input_ident.to_string()),
+            output => describe_type(output, spelled_type_name(output)),
+        };
+
+        quote::quote! {
+            #krate::__private::BuilderMetadata {
+                type_name: #input_name,
+                output: #output_descriptor,
+                dependencies: ::std::vec![#(#field_descriptors),*],
+            }
+        }
+    };
+    let main_metadata = describe(&build_as_output);
+    let metadata = quote::quote! {
+        fn metadata() -> #krate::__private::BuilderMetadata {
+            #main_metadata
+        }
+    };
+
+    let input_param = match named_field_idents.is_empty() && unnamed_field_idents.is_empty() {
+        true => quote::quote! { _ },
+        false => quote::quote! { input },
+    };
+
+    // register the additional #[build_as] outputs along with the main one
+    let (register_aliases, alias_impls) = match alias_outputs.is_empty() {
+        true => (quote::quote! {}, quote::quote! {}),
+        false if container_attrs.sync => Err(syn::Error::new_spanned(
+            &input.ident,
+            "Multiple #[build_as] outputs are not supported by #[di(sync)] builders",
+        ))?,
+        false => {
+            let alias_impls = alias_outputs.iter().map(|alias| {
+                let build_alias = alias.wrap(&construct);
+                let alias_metadata = describe(alias);
+
+                quote::quote! {
+                    impl #impl_generics #krate::__private::DIBuilderAs<#alias> for #input_ident #ty_generics #where_clause {
+                        async fn build_as(#input_param: Self::Input) -> #alias {
+                            #build_alias
+                        }
+
+                        fn metadata_as() -> #krate::__private::BuilderMetadata {
+                            #alias_metadata
+                        }
+                    }
+                }
+            });
+
+            (
+                quote::quote! {
+                    async fn register_aliases(
+                        manager: &mut #krate::__private::DIManager,
+                        lifetime: ::std::option::Option<#krate::__private::Lifetime>,
+                    ) {
+                        #(
+                            manager.register_alias::<Self, #alias_outputs>(lifetime).await;
+                        )*
+                    }
+                },
+                quote::quote! {
+                    #(#alias_impls)*
+                },
+            )
+        }
+    };
+
+    // submit the struct to the registry collected by `DIManager::from_registered_components` if #[di(auto_register)] is present
+    let auto_register = match container_attrs.auto_register {
+        false => quote::quote! {},
+        true if is_generic => Err(syn::Error::new_spanned(
+            &input.generics,
+            "Generic structs cannot be registered automatically, register their instantiations by hand instead",
+        ))?,
+        true => {
+            let lifetime = match &container_attrs.lifetime {
+                Some(variant) => quote::quote! {
+                    ::std::option::Option::Some(#krate::__private::Lifetime::#variant)
+                },
+                None => quote::quote! { ::std::option::Option::None },
+            };
+
+            quote::quote! {
+                #krate::__private::inventory::submit! {
+                    #krate::__private::Component::new::<#input_ident>(#lifetime)
+                }
+            }
+        }
+    };
+
+    // implement the blocking builder if #[di(sync)] is present, the async one otherwise
+    let output = if container_attrs.sync {
+        quote::quote! {
+            impl #impl_generics #krate::__private::SyncDIBuilder for #input_ident #ty_generics #where_clause {
+                type Input = #input_type;
                 type Output = #build_as_output;
 
-                async fn build(_: Self::Input) -> Self::Output {
+                fn build(#input_param: Self::Input) -> Self::Output {
                     #build_method
                 }
+
+                #metadata
             }
-        },
-        (false, true) | (true, false) => quote::quote! {
-            #[async_trait]
-            impl DIBuilder for #input_ident {
-                type Input = deps!(#(#field_types),*);
+
+            #auto_register
+        }
+    } else {
+        quote::quote! {
+            impl #impl_generics #krate::__private::DIBuilder for #input_ident #ty_generics #where_clause {
+                type Input = #input_type;
                 type Output = #build_as_output;
 
-                async fn build(input: Self::Input) -> Self::Output {
+                async fn build(#input_param: Self::Input) -> Self::Output {
                     #build_method
                 }
+
+                #metadata
+
+                #register_aliases
             }
-        },
-        _ => Err(syn::Error::new_spanned(
-            input,
-            "Cannot mix named and unnamed fields with #[deps]",
-        ))?,
+
+            #alias_impls
+
+            #auto_register
+        }
     };
 
     Ok(output)
 }
+
+/// Replaces the `Self` keyword with the given type, so that types written within an `impl` block can be spelled outside of it.
+fn replace_self(tokens: TokenStream, self_ty: &syn::Type) -> TokenStream {
+    tokens
+        .into_iter()
+        .flat_map(|token| match token {
+            proc_macro2::TokenTree::Ident(ident) if ident == "Self" => self_ty.to_token_stream(),
+            proc_macro2::TokenTree::Group(group) => {
+                let mut replaced = proc_macro2::Group::new(
+                    group.delimiter(),
+                    replace_self(group.stream(), self_ty),
+                );
+                replaced.set_span(group.span());
+                proc_macro2::TokenTree::Group(replaced).to_token_stream()
+            }
+            token => token.to_token_stream(),
+        })
+        .collect()
+}
+
+/// Sets the span of all the given tokens, so that an error about them is reported at the given span instead of the macro call.
+fn respan(tokens: TokenStream, span: proc_macro2::Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if let proc_macro2::TokenTree::Group(group) = &token {
+                let mut respanned =
+                    proc_macro2::Group::new(group.delimiter(), respan(group.stream(), span));
+                respanned.set_span(span);
+                token = proc_macro2::TokenTree::Group(respanned);
+            }
+
+            token.set_span(span);
+            token
+        })
+        .collect()
+}
+
+/// Finds the constructor of an `impl` block, which is either marked with `#[injectable]` or the only function of the block.
+fn find_constructor(item_impl: &mut syn::ItemImpl) -> syn::Result<syn::ImplItemFn> {
+    let mut constructor = None;
+
+    for item in &mut item_impl.items {
+        let syn::ImplItem::Fn(function) = item else {
+            continue;
+        };
+
+        let marker = function
+            .attrs
+            .iter()
+            .position(|attr| attr.path().is_ident("injectable"));
+        let Some(marker) = marker else {
+            continue;
+        };
+
+        let attr = function.attrs.remove(marker);
+        if !matches!(attr.meta, syn::Meta::Path(_)) {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "#[injectable] does not take any arguments on the constructor, expected #[injectable]",
+            ));
+        }
+
+        if constructor.is_some() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "Multiple constructors are marked with #[injectable], expected only one",
+            ));
+        }
+
+        constructor = Some(function.clone());
+    }
+
+    if let Some(constructor) = constructor {
+        return Ok(constructor);
+    }
+
+    let mut functions = item_impl.items.iter().filter_map(|item| match item {
+        syn::ImplItem::Fn(function) => Some(function),
+        _ => None,
+    });
+
+    match (functions.next(), functions.next()) {
+        (Some(function), None) => Ok(function.clone()),
+        _ => Err(syn::Error::new_spanned(
+            &item_impl.self_ty,
+            "Cannot tell which function is the constructor, mark it with #[injectable]",
+        )),
+    }
+}
+
+/// Implements the builder of a type from the signature of the function building it, e.g. a constructor or a provider.
+fn signature_builder(
+    krate: &syn::Path,
+    self_ty: &syn::Type,
+    generics: &syn::Generics,
+    sig: &syn::Signature,
+    callee: TokenStream,
+    kind: &str,
+    fallible: bool,
+) -> syn::Result<TokenStream> {
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &sig.generics,
+            format!("Generic {kind}s are not supported, as their dependencies must be known"),
+        ));
+    }
+
+    let syn::ReturnType::Type(_, output) = &sig.output else {
+        return Err(syn::Error::new_spanned(
+            sig,
+            format!("The {kind} must return the built value, e.g. Self or Box<dyn Trait>"),
+        ));
+    };
+
+    // a function returning a `Result` builds the value wrapped in it, while its error fails the build, if fallible ones are allowed
+    let ok_output = ok_type(output).filter(|_| fallible);
+    let output = ok_output.unwrap_or(output);
+
+    // get the types of the parameters, where `DIObj<T>` parameters depend on `T` and receive the shared handle
+    let param_types = sig
+        .inputs
+        .iter()
+        .map(|input| match input {
+            syn::FnArg::Typed(pat_type) => Ok(&*pat_type.ty),
+            syn::FnArg::Receiver(receiver) => Err(syn::Error::new_spanned(
+                receiver,
+                format!("The {kind} cannot take self, as it builds the value"),
+            )),
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    let dep_types = param_types
+        .iter()
+        .map(|ty| shared_handle_type(ty).unwrap_or(ty))
+        .collect::<Vec<_>>();
+    let shared_params = param_types
+        .iter()
+        .map(|ty| shared_handle_type(ty).is_some())
+        .collect::<Vec<_>>();
+
+    let input_type = input_tuple(
+        dep_types
+            .iter()
+            .map(|ty| quote::quote! { #krate::__private::DIObj<#ty> }),
+    );
+
+    // bind the parameters by destructuring the input, extracting the ones which are not shared handles
+    let arg_idents = (0..param_types.len())
+        .map(|i| syn::Ident::new(&format!("arg_{}", i), proc_macro2::Span::call_site()))
+        .collect::<Vec<_>>();
+    let pattern = input_tuple(arg_idents.iter().map(|ident| ident.to_token_stream()));
+    let extracted_idents = arg_idents
+        .iter()
+        .zip(&shared_params)
+        .filter(|(_, shared)| !**shared)
+        .map(|(ident, _)| ident);
+    let (input_param, bind_args) = match arg_idents.is_empty() {
+        true => (quote::quote! { _ }, quote::quote! {}),
+        false => (
+            quote::quote! { input },
+            quote::quote! {
+                let #pattern = input;
+                #(
+                    let #extracted_idents = #extracted_idents.extract();
+                )*
+            },
+        ),
+    };
+
+    let call = match sig.asyncness {
+        Some(_) => quote::quote! { #callee(#(#arg_idents),*).await },
+        None => quote::quote! { #callee(#(#arg_idents),*) },
+    };
+
+    // carry the generic parameters of the builder type, bounded as required by the builder traits
+    let is_generic = !generics.params.is_empty();
+    let mut bounded = generics.clone();
+    if is_generic {
+        let type_params = generics.type_params().map(|param| &param.ident);
+        let extracted_types = dep_types
+            .iter()
+            .zip(&shared_params)
+            .filter(|(_, shared)| !**shared)
+            .map(|(ty, _)| ty);
+        let where_clause = bounded.make_where_clause();
+
+        where_clause
+            .predicates
+            .extend(type_params.map(|param| -> syn::WherePredicate {
+                syn::parse_quote! { #param: ::std::marker::Send + ::std::marker::Sync + 'static }
+            }));
+        where_clause
+            .predicates
+            .extend(extracted_types.map(|ty| -> syn::WherePredicate {
+                syn::parse_quote! { #ty: ::std::clone::Clone }
+            }));
+    }
+    let (impl_generics, _, where_clause) = bounded.split_for_impl();
+
+    // describe the builder using the type names as written in the source code, or as instantiated if it is generic
+    let describe_type = |ty: &dyn ToTokens| match is_generic {
+        true => quote::quote! { #krate::__private::TypeDescriptor::of::<#ty>() },
+        false => {
+            let name = spelled_type_name(&replace_self(ty.to_token_stream(), self_ty));
+            quote::quote! { #krate::__private::TypeDescriptor::named::<#ty>(#name) }
+        }
+    };
+    let input_name = match is_generic {
+        true => quote::quote! { ::std::any::type_name::<Self>() },
+        false => {
+            let input_name = spelled_type_name(self_ty);
+            quote::quote! { #input_name }
+        }
+    };
+    let output_descriptor = describe_type(output);
+    let dep_descriptors = dep_types
+        .iter()
+        .map(|ty| describe_type(ty))
+        .collect::<Vec<_>>();
+    let metadata = quote::quote! {
+        fn metadata() -> #krate::__private::BuilderMetadata {
+            #krate::__private::BuilderMetadata {
+                type_name: #input_name,
+                output: #output_descriptor,
+                dependencies: ::std::vec![#(#dep_descriptors),*],
+            }
+        }
+    };
+
+    // implement the blocking builder for sync functions, the async one otherwise
+    let (builder_trait, asyncness) = match sig.asyncness {
+        Some(_) => (quote::quote! { DIBuilder }, quote::quote! { async }),
+        None => (quote::quote! { SyncDIBuilder }, quote::quote! {}),
+    };
+    let build_methods = match ok_output.is_some() {
+        true => {
+            let try_build = match sig.asyncness {
+                Some(_) => {
+                    quote::quote! { <Self as #krate::__private::DIBuilder>::try_build(input).await }
+                }
+                None => {
+                    quote::quote! { <Self as #krate::__private::SyncDIBuilder>::try_build(input) }
+                }
+            };
+
+            quote::quote! {
+                #asyncness fn build(input: Self::Input) -> Self::Output {
+                    match #try_build {
+                        ::std::result::Result::Ok(obj) => obj,
+                        ::std::result::Result::Err(error) => ::std::panic!("`{}`: {}", #input_name, error),
+                    }
+                }
+
+                #asyncness fn try_build(
+                    #input_param: Self::Input,
+                ) -> ::std::result::Result<Self::Output, #krate::__private::ResolveError> {
+                    #bind_args
+
+                    ::std::result::Result::map_err(#call, |_| #krate::__private::ResolveError::Failed)
+                }
+            }
+        }
+        false => quote::quote! {
+            #asyncness fn build(#input_param: Self::Input) -> Self::Output {
+                #bind_args
+
+                #call
+            }
+        },
+    };
+
+    let builder_impl = quote::quote! {
+        impl #impl_generics #krate::__private::#builder_trait for #self_ty #where_clause {
+            type Input = #input_type;
+            type Output = #output;
+
+            #build_methods
+
+            #metadata
+        }
+    };
+
+    Ok(builder_impl)
+}
+
+pub(crate) fn expand_injectable(args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let mut item_impl = match syn::parse2::<syn::ItemImpl>(item.clone()) {
+        Ok(item_impl) => item_impl,
+        Err(_) => return Err(match syn::parse2::<syn::ImplItemFn>(item) {
+            Ok(function) => syn::Error::new_spanned(
+                function.sig.ident,
+                "#[injectable] must also be placed on the impl block of the constructor",
+            ),
+            Err(_) => syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "#[injectable] can only be used on an impl block and on the constructor inside it",
+            ),
+        }),
+    };
+
+    // get the options of the attribute, which only supports overriding the path to the `yadir` crate
+    let mut krate = None;
+    let options = syn::meta::parser(|meta| {
+        if meta.path.is_ident("crate") {
+            if krate.is_some() {
+                return Err(meta.error("Multiple `crate` options are not allowed"));
+            }
+
+            let path = meta.value()?.parse::<syn::LitStr>()?;
+            krate = Some(path.parse::<syn::Path>()?);
+            Ok(())
+        } else {
+            Err(meta.error("Unsupported #[injectable] option, expected `crate`"))
+        }
+    });
+    syn::parse::Parser::parse2(options, args)?;
+    let krate = krate.unwrap_or_else(|| syn::parse_quote! { ::yadir });
+
+    if let Some((_, path, _)) = &item_impl.trait_ {
+        return Err(syn::Error::new_spanned(
+            path,
+            "#[injectable] can only be used on inherent impl blocks",
+        ));
+    }
+
+    if let Some(lifetime) = item_impl.generics.lifetimes().next() {
+        return Err(syn::Error::new_spanned(
+            lifetime,
+            "Lifetime parameters are not supported, as dependencies must be 'static",
+        ));
+    }
+
+    let constructor = find_constructor(&mut item_impl)?;
+    let constructor_ident = &constructor.sig.ident;
+
+    let builder_impl = signature_builder(
+        &krate,
+        &item_impl.self_ty,
+        &item_impl.generics,
+        &constructor.sig,
+        quote::quote! { Self::#constructor_ident },
+        "constructor",
+        true,
+    )?;
+
+    Ok(quote::quote! {
+        #item_impl
+
+        #builder_impl
+    })
+}
+
+pub(crate) fn expand_provider(args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let function = syn::parse2::<syn::ItemFn>(item).map_err(|e| {
+        syn::Error::new(
+            e.span(),
+            "#[provider] can only be used on free functions, e.g. #[provider] async fn make_store() -> Store",
+        )
+    })?;
+
+    // get the options of the attribute, i.e. the lifetime of the provider and the path to the `yadir` crate
+    let (mut lifetime, mut krate) = (None, None);
+    let options = syn::meta::parser(|meta| {
+        if meta.path.is_ident("lifetime") {
+            if lifetime.is_some() {
+                return Err(meta.error("Multiple `lifetime` options are not allowed"));
+            }
+
+            lifetime = Some(lifetime_variant(&meta.value()?.parse()?)?);
+            Ok(())
+        } else if meta.path.is_ident("crate") {
+            if krate.is_some() {
+                return Err(meta.error("Multiple `crate` options are not allowed"));
+            }
+
+            let path = meta.value()?.parse::<syn::LitStr>()?;
+            krate = Some(path.parse::<syn::Path>()?);
+            Ok(())
+        } else {
+            Err(meta.error("Unsupported #[provider] option, expected `lifetime` or `crate`"))
+        }
+    });
+    syn::parse::Parser::parse2(options, args)?;
+    let krate = krate.unwrap_or_else(|| syn::parse_quote! { ::yadir });
+    let lifetime = match lifetime {
+        Some(variant) => quote::quote! {
+            ::std::option::Option::Some(#krate::__private::Lifetime::#variant)
+        },
+        None => quote::quote! { ::std::option::Option::None },
+    };
+
+    // the provider becomes a unit struct named after the function, so that the function name can be passed around as a value,
+    // documented like the function while its other attributes stay on the `provide` method
+    let syn::ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = function;
+    let provider_ident = &sig.ident;
+    let provider_ty: syn::Type = syn::parse_quote! { #provider_ident };
+    let docs = attrs.iter().filter(|attr| attr.path().is_ident("doc"));
+    let mut provide_sig = sig.clone();
+    provide_sig.ident = syn::Ident::new("provide", sig.ident.span());
+
+    let builder_impl = signature_builder(
+        &krate,
+        &provider_ty,
+        &syn::Generics::default(),
+        &sig,
+        quote::quote! { Self::provide },
+        "provider",
+        false,
+    )?;
+
+    Ok(quote::quote! {
+        #(#docs)*
+        #[allow(non_camel_case_types)]
+        #vis struct #provider_ident;
+
+        impl #provider_ident {
+            #(#attrs)*
+            #vis #provide_sig #block
+        }
+
+        #builder_impl
+
+        impl #krate::__private::Provider for #provider_ident {
+            const LIFETIME: ::std::option::Option<#krate::__private::Lifetime> = #lifetime;
+        }
+    })
+}
+
+pub(crate) fn expand_container(input: StaticContainer) -> syn::Result<TokenStream> {
+    let StaticContainer {
+        attrs,
+        vis,
+        ident,
+        krate,
+        entries,
+    } = input;
+    let krate = krate.unwrap_or_else(|| syn::parse_quote! { ::yadir });
+
+    // dependencies are resolved by output type, hence each builder can only be registered once
+    let mut builders = std::collections::HashMap::new();
+    let mut accessors = std::collections::HashSet::new();
+    for entry in &entries {
+        let builder = spelled_type_name(&entry.builder);
+        if let Some(registered) = builders.insert(builder.clone(), &entry.accessor) {
+            return Err(syn::Error::new_spanned(
+                &entry.builder,
+                format!(
+                    "`{builder}` is already registered as `{registered}`, as dependencies are resolved by output type, \
+                     each output can only be registered once"
+                ),
+            ));
+        }
+
+        if !accessors.insert(entry.accessor.to_string()) {
+            return Err(syn::Error::new_spanned(
+                &entry.accessor,
+                format!(
+                    "Duplicate accessor `{}` for distinct builders, rename one of them via e.g. `Builder as name: singleton`",
+                    entry.accessor
+                ),
+            ));
+        }
+    }
+
+    // each singleton is stored in a dedicated field, while transients are built on every access
+    let singletons = entries
+        .iter()
+        .filter(|entry| entry.lifetime == "Singleton")
+        .collect::<Vec<_>>();
+    let fields = singletons.iter().map(|entry| {
+        let (accessor, builder) = (&entry.accessor, &entry.builder);
+        quote::quote! {
+            #accessor: ::std::option::Option<
+                #krate::__private::DIObj<<#builder as #krate::__private::DIBuilder>::Output>,
+            >
+        }
+    });
+    let singleton_accessors = singletons.iter().map(|entry| &entry.accessor);
+    let singleton_accessors_init = singleton_accessors.clone();
+
+    let accessors = entries.iter().map(|entry| {
+        let (accessor, builder) = (&entry.accessor, &entry.builder);
+        let output = quote::quote! { <#builder as #krate::__private::DIBuilder>::Output };
+        let doc = format!(
+            "Resolves the `{}` dependency, registered as {}.",
+            spelled_type_name(builder),
+            entry.lifetime.to_string().to_lowercase()
+        );
+        // spanned at the builder, so that a missing dependency is reported where the builder is registered
+        let build = quote::quote_spanned! {builder.span()=>
+            let input = <<#builder as #krate::__private::DIBuilder>::Input as #krate::__private::ResolveInput<Self>>::resolve_input(self).await;
+            let obj = #krate::__private::DIObj::new(<#builder as #krate::__private::DIBuilder>::build(input).await);
+        };
+
+        let body = match entry.lifetime == "Singleton" {
+            true => quote::quote! {
+                if let ::std::option::Option::Some(obj) = &self.#accessor {
+                    return obj.clone();
+                }
+
+                #build
+                self.#accessor = ::std::option::Option::Some(obj.clone());
+                obj
+            },
+            false => quote::quote! {
+                #build
+                obj
+            },
+        };
+
+        quote::quote! {
+            #[doc = #doc]
+            #vis async fn #accessor(&mut self) -> #krate::__private::DIObj<#output> {
+                #body
+            }
+        }
+    });
+
+    let provides = entries.iter().map(|entry| {
+        let (accessor, builder) = (&entry.accessor, &entry.builder);
+        let output = quote::quote! { <#builder as #krate::__private::DIBuilder>::Output };
+        let acyclic = respan(
+            quote::quote! {
+                <#builder as #krate::__private::DIBuilder>::Input: #krate::__private::AcyclicInput<#ident>
+            },
+            builder.span(),
+        );
+
+        // spanned at the builder, so that two builders with the same output conflict where the latter is registered, while
+        // private builders remain allowed in public containers as with any other generated code
+        quote::quote_spanned! {builder.span()=>
+            #[allow(private_interfaces)]
+            impl #krate::__private::Provides<#output> for #ident {
+                fn provide(
+                    &mut self,
+                ) -> impl ::std::future::Future<Output = #krate::__private::DIObj<#output>> + ::std::marker::Send {
+                    self.#accessor()
+                }
+            }
+
+            // a dependency cycle overflows while evaluating the bound, which is checked as it does not depend on any parameter
+            #[allow(private_bounds)]
+            impl #krate::__private::Acyclic<#ident> for #output
+            where
+                #acyclic,
+            {
+            }
+        }
+    });
+
+    Ok(quote::quote! {
+        #(#attrs)*
+        #vis struct #ident {
+            #(#fields,)*
+        }
+
+        impl #ident {
+            /// Creates the container, building all the singletons in declaration order, each one after the dependencies it needs.
+            #vis async fn new() -> Self {
+                let mut container = Self {
+                    #(#singleton_accessors_init: ::std::option::Option::None,)*
+                };
+                #(container.#singleton_accessors().await;)*
+
+                container
+            }
+
+            #(#accessors)*
+        }
+
+        #(#provides)*
+    })
+}
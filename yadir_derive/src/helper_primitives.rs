@@ -1,19 +1,189 @@
 use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::Token;
 
 pub(crate) enum BuildMethod {
     None,
-    New,
     Default,
+    Constructor(Constructor),
 }
 
-impl TryFrom<String> for BuildMethod {
-    type Error = syn::Error;
+/// A constructor called with the #[deps] fields to build the struct, as specified via `#[build_method(...)]`.
+pub(crate) struct Constructor {
+    pub(crate) path: syn::ExprPath,
+    pub(crate) is_async: bool,
+    pub(crate) args: Option<Punctuated<syn::Member, Token![,]>>,
+}
+
+/// Parses the name or the path of a constructor, where a name refers to an associated function of the struct.
+fn parse_constructor_path(input: ParseStream) -> syn::Result<(syn::ExprPath, Option<String>)> {
+    if input.peek(syn::LitStr) {
+        let name = input.parse::<syn::LitStr>()?;
+        let ident = name.parse::<syn::Ident>().map_err(|_| {
+            syn::Error::new_spanned(
+                &name,
+                "Invalid build method, expected the name of an associated function, e.g. \"new\"",
+            )
+        })?;
+
+        Ok((
+            syn::parse_quote_spanned! { name.span()=> Self::#ident },
+            Some(name.value()),
+        ))
+    } else {
+        Ok((input.parse()?, None))
+    }
+}
 
-    fn try_from(value: String) -> Result<Self, Self::Error> {
-        match value.as_str() {
-            "new" => Ok(Self::New),
-            "default" => Ok(Self::Default),
-            _ => Err(syn::Error::new_spanned(value, "Invalid build method")),
+impl Parse for BuildMethod {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let is_async = input.peek(Token![async]);
+        if is_async {
+            input.parse::<Token![async]>()?;
+            input.parse::<Token![=]>()?;
+        }
+
+        let (path, name) = parse_constructor_path(input)?;
+
+        let mut args = None;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let option = input.parse::<syn::Ident>()?;
+            if option != "args" {
+                return Err(syn::Error::new_spanned(
+                    option,
+                    "Unsupported #[build_method] option, expected `args`",
+                ));
+            }
+
+            let content;
+            syn::parenthesized!(content in input);
+            args = Some(Punctuated::parse_terminated(&content)?);
+        }
+
+        match (name.as_deref(), is_async, args) {
+            (Some("default"), false, None) => Ok(Self::Default),
+            (Some("default"), false, Some(args)) => Err(syn::Error::new_spanned(
+                args,
+                "The `default` build method does not take any arguments",
+            )),
+            (_, is_async, args) => Ok(Self::Constructor(Constructor {
+                path,
+                is_async,
+                args,
+            })),
+        }
+    }
+}
+
+/// Maps a lifetime given as `"singleton"` or `"transient"` to the matching `Lifetime` variant.
+pub(crate) fn lifetime_variant(lifetime: &syn::LitStr) -> syn::Result<syn::Ident> {
+    let variant = match lifetime.value().as_str() {
+        "singleton" => "Singleton",
+        "transient" => "Transient",
+        _ => {
+            return Err(syn::Error::new_spanned(
+                lifetime,
+                "Invalid lifetime, expected `singleton` or `transient`",
+            ))
+        }
+    };
+
+    Ok(syn::Ident::new(variant, lifetime.span()))
+}
+
+/// The options specified on a struct via `#[di(...)]` attributes.
+#[derive(Default)]
+pub(crate) struct ContainerAttributes {
+    pub(crate) sync: bool,
+    pub(crate) auto_register: bool,
+    /// The `Lifetime` variant the struct is registered with when it is registered automatically.
+    pub(crate) lifetime: Option<syn::Ident>,
+    /// The path to the `yadir` crate, for crates which rename or re-export it.
+    pub(crate) krate: Option<syn::Path>,
+}
+
+impl ContainerAttributes {
+    pub(crate) fn new(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut container_attrs = Self::default();
+        let mut lifetime_attr = None;
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("di")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("sync") {
+                    if container_attrs.sync {
+                        return Err(meta.error("Multiple `sync` options are redundant"));
+                    }
+
+                    container_attrs.sync = true;
+                    Ok(())
+                } else if meta.path.is_ident("auto_register") {
+                    if container_attrs.auto_register {
+                        return Err(meta.error("Multiple `auto_register` options are redundant"));
+                    }
+
+                    container_attrs.auto_register = true;
+                    Ok(())
+                } else if meta.path.is_ident("lifetime") {
+                    if container_attrs.lifetime.is_some() {
+                        return Err(meta.error("Multiple `lifetime` options are not allowed"));
+                    }
+
+                    container_attrs.lifetime = Some(lifetime_variant(&meta.value()?.parse()?)?);
+                    lifetime_attr = Some(attr);
+                    Ok(())
+                } else if meta.path.is_ident("crate") {
+                    if container_attrs.krate.is_some() {
+                        return Err(meta.error("Multiple `crate` options are not allowed"));
+                    }
+
+                    let path = meta.value()?.parse::<syn::LitStr>()?;
+                    container_attrs.krate = Some(path.parse::<syn::Path>()?);
+                    Ok(())
+                } else {
+                    Err(meta.error(
+                        "Unsupported #[di] option, expected `sync`, `lifetime`, `auto_register` or `crate`",
+                    ))
+                }
+            })?;
+        }
+
+        match lifetime_attr {
+            Some(attr) if !container_attrs.auto_register => Err(syn::Error::new_spanned(
+                attr,
+                "The `lifetime` option is only used along with `auto_register`",
+            )),
+            _ => Ok(container_attrs),
+        }
+    }
+
+    /// Returns the path the generated code refers to the `yadir` crate by.
+    pub(crate) fn crate_path(&self) -> syn::Path {
+        self.krate
+            .clone()
+            .unwrap_or_else(|| syn::parse_quote! { ::yadir })
+    }
+}
+
+/// The options specified on a field via `#[deps(...)]`, mapping the dependency resolved from the container into the field.
+#[derive(Default)]
+pub(crate) struct DepsOptions {
+    /// The type resolved from the container, if it differs from the type of the field.
+    pub(crate) from: Option<syn::Type>,
+    /// The function mapping the resolved dependency into the field.
+    pub(crate) with: Option<syn::Expr>,
+}
+
+impl DepsOptions {
+    /// Maps the bound dependency into the field, via `with` if given or via `Into` if only `from` is given.
+    pub(crate) fn map(&self, ident: &syn::Ident) -> Option<TokenStream> {
+        match (&self.from, &self.with) {
+            (_, Some(with)) => Some(quote::quote! { let #ident = (#with)(#ident); }),
+            (Some(_), None) => {
+                Some(quote::quote! { let #ident = ::std::convert::Into::into(#ident); })
+            }
+            (None, None) => None,
         }
     }
 }
@@ -28,6 +198,11 @@ impl<'f> StructField<'f> {
     }
 
     pub(crate) fn is_deps(&self) -> syn::Result<bool> {
+        Ok(self.deps_options()?.is_some())
+    }
+
+    /// Returns the options given via `#[deps(...)]`, if the field is annotated with `#[deps]`.
+    pub(crate) fn deps_options(&self) -> syn::Result<Option<DepsOptions>> {
         let deps_attrs = self
             .field
             .attrs
@@ -36,19 +211,171 @@ impl<'f> StructField<'f> {
             .collect::<Vec<_>>();
 
         match deps_attrs.len() {
-            0 => Ok(false),
-            1 => Ok(true),
-            _ => Err(syn::Error::new_spanned(
-                deps_attrs[1].path(),
-                "Multiple #[deps] attributes are redundant",
-            )),
+            0 => return Ok(None),
+            1 => {}
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    deps_attrs[1].path(),
+                    "Multiple #[deps] attributes are redundant",
+                ))
+            }
         }
+
+        let mut options = DepsOptions::default();
+        if let syn::Meta::Path(_) = deps_attrs[0].meta {
+            return Ok(Some(options));
+        }
+
+        deps_attrs[0].parse_nested_meta(|meta| {
+            if meta.path.is_ident("from") {
+                if options.from.is_some() {
+                    return Err(meta.error("Multiple `from` options are not allowed"));
+                }
+
+                options.from = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("with") {
+                if options.with.is_some() {
+                    return Err(meta.error("Multiple `with` options are not allowed"));
+                }
+
+                options.with = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("Unsupported #[deps] option, expected `from` or `with`"))
+            }
+        })?;
+
+        Ok(Some(options))
+    }
+
+    /// Returns the expression given via `#[di(default = ...)]` to initialize a field which is not injected, if any.
+    pub(crate) fn default_expr(&self) -> syn::Result<Option<syn::Expr>> {
+        let mut default_expr = None;
+
+        for attr in self
+            .field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("di"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("default") {
+                    if default_expr.is_some() {
+                        return Err(meta.error("Multiple `default` options are redundant"));
+                    }
+
+                    default_expr = Some(meta.value()?.parse::<syn::Expr>()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("Unsupported #[di] field option, expected `default`"))
+                }
+            })?;
+        }
+
+        if let Some(expr) = &default_expr {
+            if self.is_deps()? {
+                return Err(syn::Error::new_spanned(
+                    expr,
+                    "#[deps] fields are injected and cannot have a default value",
+                ));
+            }
+        }
+
+        Ok(default_expr)
     }
 }
 
+/// Returns the type of the dependency shared by a `DIObj<T>` field, which is injected as is instead of being extracted.
+pub(crate) fn shared_handle_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "DIObj" {
+        return None;
+    }
+
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the type of the value built by a fallible function, i.e. the success type of its `Result`, e.g. `Self` for
+/// `Result<Self, E>` or `io::Result<Self>`.
+pub(crate) fn ok_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if type_path.qself.is_some() || segment.ident != "Result" {
+        return None;
+    }
+
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if matches!(args.args.len(), 1 | 2) => {
+            match &args.args[0] {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The output type of a builder, along with the way the built struct is converted into it.
 pub(crate) enum TypeOutput {
     SelfType,
     BoxedTraitObjectType(syn::Type),
+    SharedTraitObjectType(syn::Type),
+    Converted(syn::Type),
+}
+
+impl TypeOutput {
+    /// Classifies a `#[build_as]` type by its outermost smart pointer, if any.
+    pub(crate) fn new(ty: syn::Type) -> syn::Result<Self> {
+        let pointer = match &ty {
+            syn::Type::Path(type_path) if type_path.qself.is_none() => type_path
+                .path
+                .segments
+                .last()
+                .filter(|segment| {
+                    matches!(segment.arguments, syn::PathArguments::AngleBracketed(_))
+                })
+                .map(|segment| segment.ident.to_string()),
+            _ => None,
+        };
+
+        match pointer.as_deref() {
+            Some("Box") => Ok(Self::BoxedTraitObjectType(ty)),
+            Some("Arc") => Ok(Self::SharedTraitObjectType(ty)),
+            Some("Rc") => Err(syn::Error::new_spanned(
+                ty,
+                "Rc outputs cannot be shared across threads, use Arc instead",
+            )),
+            _ => Ok(Self::Converted(ty)),
+        }
+    }
+
+    /// Converts the expression building the struct into the output type.
+    pub(crate) fn wrap(&self, build: &TokenStream) -> TokenStream {
+        match self {
+            Self::SelfType => build.clone(),
+            Self::BoxedTraitObjectType(_) => quote::quote! {
+                ::std::boxed::Box::new({ #build })
+            },
+            Self::SharedTraitObjectType(_) => quote::quote! {
+                ::std::sync::Arc::new({ #build })
+            },
+            Self::Converted(_) => quote::quote! {
+                ::std::convert::Into::into({ #build })
+            },
+        }
+    }
 }
 
 impl quote::ToTokens for TypeOutput {
@@ -57,10 +384,176 @@ impl quote::ToTokens for TypeOutput {
             Self::SelfType => quote::quote! {
                 Self
             },
-            Self::BoxedTraitObjectType(ty) => quote::quote! {
+            Self::BoxedTraitObjectType(ty)
+            | Self::SharedTraitObjectType(ty)
+            | Self::Converted(ty) => quote::quote! {
                 #ty
             },
         }
         .to_tokens(tokens)
     }
 }
+
+/// The largest flat tuple implementing `GetInput`, beyond which the input is nested as pairs instead.
+const MAX_FLAT_INPUT_ARITY: usize = 16;
+
+/// Spells out the input of a builder as `deps!` would expand it, i.e. as a flat tuple, or as nested pairs for larger inputs.
+/// The same shape is used both for the type of the input and for the pattern destructuring it.
+pub(crate) fn input_tuple(
+    items: impl DoubleEndedIterator<Item = TokenStream> + ExactSizeIterator,
+) -> TokenStream {
+    match items.len() > MAX_FLAT_INPUT_ARITY {
+        true => items.rev().fold(
+            quote::quote! { () },
+            |rest, item| quote::quote! { (#item, #rest) },
+        ),
+        false => quote::quote! { (#(#items,)*) },
+    }
+}
+
+/// Renders a type as spelled in the source code, without the spacing introduced by the token stream printer.
+pub(crate) fn spelled_type_name(ty: &impl ToTokens) -> String {
+    let mut name = ty.to_token_stream().to_string();
+
+    for (spaced, compact) in [
+        (" :: ", "::"),
+        (":: ", "::"),
+        (" < ", "<"),
+        ("< ", "<"),
+        (" <", "<"),
+        (" >", ">"),
+        (" ,", ","),
+        ("& ", "&"),
+        ("( ", "("),
+        (" )", ")"),
+        ("[ ", "["),
+        (" ]", "]"),
+        (" ;", ";"),
+    ] {
+        name = name.replace(spaced, compact);
+    }
+
+    name
+}
+
+/// A static container declared via `container! { struct AppContainer { Bar: singleton, Foo: transient } }`.
+pub(crate) struct StaticContainer {
+    pub(crate) attrs: Vec<syn::Attribute>,
+    pub(crate) vis: syn::Visibility,
+    pub(crate) ident: syn::Ident,
+    /// The path to the `yadir` crate, as specified via `#[di(crate = "path")]`.
+    pub(crate) krate: Option<syn::Path>,
+    pub(crate) entries: Punctuated<StaticContainerEntry, Token![,]>,
+}
+
+/// A builder registered in a static container, e.g. `Foo: singleton` or `Foo as foo_service: transient`.
+pub(crate) struct StaticContainerEntry {
+    pub(crate) builder: syn::Type,
+    /// The name of the accessor resolving the dependency, defaulting to the builder name in snake case.
+    pub(crate) accessor: syn::Ident,
+    /// The `Lifetime` variant the builder is registered with.
+    pub(crate) lifetime: syn::Ident,
+}
+
+impl Parse for StaticContainer {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut attrs = input.call(syn::Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<Token![struct]>()?;
+        let ident = input.parse()?;
+
+        let content;
+        syn::braced!(content in input);
+        let entries = Punctuated::parse_terminated(&content)?;
+
+        // the `#[di]` attributes configure the macro, while any other attribute is kept on the generated struct
+        let mut krate = None;
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("di")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("crate") {
+                    if krate.is_some() {
+                        return Err(meta.error("Multiple `crate` options are not allowed"));
+                    }
+
+                    let path = meta.value()?.parse::<syn::LitStr>()?;
+                    krate = Some(path.parse::<syn::Path>()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("Unsupported #[di] option, expected `crate`"))
+                }
+            })?;
+        }
+        attrs.retain(|attr| !attr.path().is_ident("di"));
+
+        Ok(Self {
+            attrs,
+            vis,
+            ident,
+            krate,
+            entries,
+        })
+    }
+}
+
+impl Parse for StaticContainerEntry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let builder = input.parse::<syn::Type>()?;
+
+        let accessor = match input.parse::<Option<Token![as]>>()? {
+            Some(_) => input.parse::<syn::Ident>()?,
+            None => accessor_name(&builder)?,
+        };
+
+        input.parse::<Token![:]>()?;
+        let lifetime = input.parse::<syn::Ident>()?;
+        if lifetime == "scoped" {
+            return Err(syn::Error::new_spanned(
+                lifetime,
+                "Scoped lifetimes are not supported, expected `singleton` or `transient`",
+            ));
+        }
+        let lifetime = lifetime_variant(&syn::LitStr::new(&lifetime.to_string(), lifetime.span()))?;
+
+        Ok(Self {
+            builder,
+            accessor,
+            lifetime,
+        })
+    }
+}
+
+/// Derives the name of the accessor of a builder from the last segment of its path, in snake case, e.g. `http_client` for `HttpClient`.
+fn accessor_name(builder: &syn::Type) -> syn::Result<syn::Ident> {
+    let syn::Type::Path(syn::TypePath { qself: None, path }) = builder else {
+        return Err(syn::Error::new_spanned(
+            builder,
+            "Cannot name the accessor of this builder, name it via e.g. `Builder as name: singleton`",
+        ));
+    };
+    let ident = &path.segments.last().expect("paths are never empty").ident;
+
+    // a word starts at an uppercase letter following a lowercase one, or ending an acronym, e.g. `HttpClient` or `HTTPClient`
+    let chars = ident.to_string().chars().collect::<Vec<_>>();
+    let mut name = String::new();
+    for (index, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && index > 0 {
+            let after_lowercase = !chars[index - 1].is_uppercase() && chars[index - 1] != '_';
+            let ends_acronym = chars[index - 1].is_uppercase()
+                && chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+
+            if after_lowercase || ends_acronym {
+                name.push('_');
+            }
+        }
+        name.extend(c.to_lowercase());
+    }
+
+    syn::parse_str::<syn::Ident>(&name)
+        .map(|accessor| syn::Ident::new(&accessor.to_string(), ident.span()))
+        .map_err(|_| {
+            syn::Error::new_spanned(
+                builder,
+                "Cannot name the accessor of this builder, name it via e.g. `Builder as name: singleton`",
+            )
+        })
+}
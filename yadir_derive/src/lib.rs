@@ -2,29 +2,72 @@
 //!
 //! This crate provides helpful procedural macros for the `yadir` crate.
 
-use crate::expand_handlers::expand_di_builder;
+use crate::expand_handlers::{
+    expand_container, expand_di_builder, expand_injectable, expand_provider, struct_item,
+};
+use crate::helper_primitives::StaticContainer;
 
 mod expand_handlers;
 mod helper_primitives;
 
 /// Derive the `DIBuilder` trait for a struct.
 ///
-/// This proc macro is used to automatically derive the `DIBuilder` trait for a struct.
+/// This proc macro is used to automatically derive the `DIBuilder` trait for a struct, as enums and unions are not supported.
 /// The `DIBuilder` trait is used to build a dependency by specifying the input/dependencies, the output, and the build method for a given dependency.
 ///
 /// The `#[derive(DIBuilder)]` macro provides a few helper attributes to customize the behavior of the builder:
 /// - `#[build_as]`: Specifies the output type of the builder. If this attribute is not present, the output type will be the input struct itself.
+///   The input struct is wrapped via `Box::new` for `Box<...>` outputs, via `Arc::new` for `Arc<...>` outputs and converted via `Into` for
+///   any other output, e.g. a newtype implementing `From` for the input struct. `Rc<...>` outputs are rejected, as they cannot be shared
+///   across threads. When several outputs are listed, e.g. `#[build_as(Arc<dyn Printer>, Arc<dyn Writer>)]`, the first one is the main
+///   output, while the others are implemented via `DIBuilderAs`, registered along with the main one by `DIManager::register` and resolved
+///   via `DIManager::resolve_as`.
 /// - `#[build_method]`: Specifies the method to build the dependency, which can be one of the following:
-///    - `new`: Calls the `new` method on the input struct.
-///    - `default`: Calls the `Default` trait implementation for the input struct.
-///    - `None` (the attribute is missing): Directly instantiates the input struct.
-/// - `#[deps]`: Specifies the fields that are input dependencies for the builder.
+///    - `"new"` or any other name: Calls the associated function with that name on the input struct, e.g. `Self::new`.
+///    - `"default"`: Calls the `Default` trait implementation for the input struct if it has no `#[deps]` fields, or directly
+///      instantiates it otherwise, in which case the struct itself does not need to implement `Default`.
+///    - A path, e.g. `Self::with_pool` or `path::to::constructor`: Calls the given function.
+///    - `async = "connect"` or `async = Self::connect`: Calls the given async function and awaits it (not supported by `#[di(sync)]`).
+///    - `None` (the attribute is missing): Directly instantiates the input struct, injecting the `#[deps]` fields and
+///      initializing the other fields via `Default::default()` or the expression given via `#[di(default = ...)]`.
 ///
-/// Rules for attributes usage:
+///   The `#[deps]` fields are passed to the function in the order they are declared in the struct, unless the order is given
+///   via `args(...)`, e.g. `#[build_method(Self::with_pool, args(pool, config))]`, or `args(1, 0)` for tuple structs.
+/// - `#[deps]`: Specifies the fields that are input dependencies for the builder. A `#[deps]` field of type `DIObj<T>` depends
+///   on `T` and receives the shared handle to it, so that `T` does not need to be `Clone`, while any other field receives a clone
+///   of the dependency.
+/// - `#[deps(from = Type, with = path::to::fn)]`: Resolves the dependency as `Type` instead of the type of the field and maps it
+///   into the field via the given function or closure, e.g. `#[deps(from = Config, with = |config: Config| config.timeout)]`.
+///   Without `with`, the dependency is converted via `Into`, and without `from`, the function maps a dependency of the type of the
+///   field. A `from = DIObj<T>` source depends on `T` and passes the shared handle to the function, e.g. to open a connection
+///   from a `DIObj<Pool>`. The derived `Input` and metadata list the resolved types, so that the dependency graph stays accurate.
+/// - `#[di(sync)]`: Implements the blocking `SyncDIBuilder` trait instead of the async `DIBuilder` one, so that the dependency
+///   can be registered and resolved without any async runtime via `DIManager::register_sync` and `DIManager::resolve_sync`.
+/// - `#[di(auto_register)]`: Submits the struct to the link-time registry of the `registry` feature of `yadir`, so that it is registered
+///   by `DIManager::from_registered_components` along with the other annotated structs across all the linked crates. The lifetime it is
+///   registered with is given via `#[di(lifetime = "singleton")]` or `#[di(lifetime = "transient")]`, defaulting to transient.
+///   Generic structs cannot be registered automatically.
+/// - `#[di(crate = "path")]`: Specifies the path to the `yadir` crate, for crates which rename or re-export it. Defaults to `::yadir`.
+/// - `#[di(default = expr)]`: Initializes a field which is not a `#[deps]` one with the given expression instead of `Default::default()`
+///   when the input struct is directly instantiated.
+///
+/// The generated code only refers to fully qualified paths, so that deriving `DIBuilder` does not require importing anything else.
+///
+/// The derived builder also describes itself via `DIBuilder::metadata`, keeping the type names of the struct, its output
+/// and its dependencies as written in the struct definition, so that the dependency graph can be inspected without building anything.
+///
+/// Generic structs are supported as long as they have no lifetime parameters. Their type and const parameters, bounds and `where`
+/// clauses are carried into the derived impl, which additionally requires the type parameters to be `Send + Sync + 'static` and the
+/// `#[deps]` fields which are not `DIObj<T>` handles to be `Clone`. As the type names of generic structs depend on their instantiation,
+/// they are described via `std::any::type_name` instead.
+///
+/// Rules for attributes usage, where any misuse is reported as a compile error pointing at the offending attribute:
 /// - `#[build_as]` is optional on the struct
 /// - `#[build_method]` is optional on the struct
 /// - `#[deps]` is optional on the fields
 /// - `#[deps]` can only be used on fields and no more than once per field
+/// - `#[di(default = expr)]` can only be used on fields which are not annotated with `#[deps]`
+/// - `#[di(lifetime = ...)]` can only be used along with `#[di(auto_register)]`
 /// - `#[build_as]` can only be used once and always before `#[build_method]`
 /// - `#[build_method]` can only be used once and always after `#[build_as]`
 ///
@@ -32,7 +75,7 @@ mod helper_primitives;
 ///
 /// ```ignore
 ///
-/// trait Printer: Sync + Send + DynClone {
+/// trait Printer: Sync + Send {
 ///    fn print(&self) -> String;
 /// }
 ///
@@ -64,7 +107,7 @@ mod helper_primitives;
 /// #[build_method("new")]
 /// struct Foo {
 ///    #[deps]
-///    bar: Box<dyn Printer>,
+///    bar: DIObj<Box<dyn Printer>>,
 ///    #[deps]
 ///    baz: Baz,
 ///    #[deps]
@@ -72,15 +115,151 @@ mod helper_primitives;
 /// }
 ///
 /// impl Foo {
-///     pub fn new(bar: Box<dyn Printer>, baz: Baz, qux: Qux) -> Self {
+///     pub fn new(bar: DIObj<Box<dyn Printer>>, baz: Baz, qux: Qux) -> Self {
 ///        Self { bar, baz, qux }
 ///     }
 /// }
 /// ```
-#[proc_macro_derive(DIBuilder, attributes(build_as, build_method, deps))]
+#[proc_macro_derive(DIBuilder, attributes(build_as, build_method, deps, di))]
 pub fn derive_di_builder(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let input = syn::parse_macro_input!(input as syn::ItemStruct);
-    expand_di_builder(input)
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    struct_item(input)
+        .and_then(expand_di_builder)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implement the `DIBuilder` trait from the signature of a constructor.
+///
+/// This attribute macro is placed on an `impl` block, whose constructor is either its only function or the one marked with
+/// `#[injectable]`, so that the dependencies are taken from the parameters of the constructor instead of the fields of the struct.
+///
+/// - Each parameter is an input dependency. A parameter of type `DIObj<T>` depends on `T` and receives the shared handle to it,
+///   so that `T` does not need to be `Clone`, while any other parameter receives a clone of the dependency.
+/// - The output of the builder is the return type of the constructor, e.g. `Self` or `Box<dyn Trait>`, unless it is spelled as a
+///   `Result`, e.g. `Result<Self, E>` or `io::Result<Self>`. Such a fallible constructor builds the success type, while an error fails
+///   the build with `ResolveError::Failed`, leaving the dependency unregistered by `DIManager::register` and making
+///   `DIManager::try_resolve` return the error instead of panicking. A type alias of a `Result` is registered as is.
+/// - A sync constructor implements the blocking `SyncDIBuilder` trait, while an async one implements the `DIBuilder` trait.
+/// - `#[injectable(crate = "path")]`: Specifies the path to the `yadir` crate, for crates which rename or re-export it.
+///
+/// Generic `impl` blocks are supported under the same rules as generic structs deriving `DIBuilder`, while constructors
+/// taking `self` or having their own generic parameters are rejected.
+///
+/// # Example
+///
+/// ```ignore
+///
+/// trait Repo: Sync + Send {
+///     fn find(&self) -> String;
+/// }
+///
+/// #[derive(Clone)]
+/// struct Config {
+///     table: String,
+/// }
+///
+/// struct Service {
+///     query: String,
+/// }
+///
+/// #[injectable]
+/// impl Service {
+///     #[injectable]
+///     async fn connect(repo: DIObj<Box<dyn Repo>>, config: Config) -> Result<Self, String> {
+///         let repo = repo.read().map_err(|e| e.to_string())?;
+///         Ok(Self { query: format!("{} from {}", repo.find(), config.table) })
+///     }
+///
+///     fn query(&self) -> &str {
+///         &self.query
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn injectable(
+    args: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    expand_injectable(args.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Turn a function building a dependency into a provider, implementing the `DIBuilder` and `Provider` traits.
+///
+/// This attribute macro is placed on a free function, sync or async, building a type which cannot derive `DIBuilder`, e.g. a type
+/// defined by another crate. The function is turned into a unit struct with the same name, so that it can be registered via
+/// `DIManager::register_provider(make_store)` and resolved via `DIManager::resolve::<make_store>()`, while its body can still be called
+/// via `make_store::provide(...)`. The doc comments of the function document the struct, while its other attributes are kept on
+/// `provide`.
+///
+/// - Each parameter is an input dependency, following the same rules as the parameters of `#[injectable]` constructors.
+/// - The output of the builder is the return type of the function.
+/// - `#[provider(lifetime = "singleton")]` or `#[provider(lifetime = "transient")]`: Specifies the lifetime the provider is registered
+///   with by `DIManager::register_provider`, defaulting to transient.
+/// - `#[provider(crate = "path")]`: Specifies the path to the `yadir` crate, for crates which rename or re-export it.
+///
+/// # Example
+///
+/// ```ignore
+///
+/// trait Store: Sync + Send {
+///     fn get(&self, key: &str) -> Option<String>;
+/// }
+///
+/// #[provider(lifetime = "singleton")]
+/// async fn make_store(cfg: Options<Cfg>) -> Box<dyn Store> {
+///     Box::new(RedisStore::connect(&cfg.url).await)
+/// }
+///
+/// let mut manager = DIManager::default();
+/// manager.register_provider(make_store).await;
+/// ```
+#[proc_macro_attribute]
+pub fn provider(
+    args: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    expand_provider(args.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generate a static container, resolving the registered dependencies via typed accessors instead of type map lookups.
+///
+/// This function-like macro declares a struct listing the builders it registers along with their lifetimes, either `singleton`
+/// or `transient`, while `scoped` is out of scope and rejected, as scopes are not supported by `DIManager` either. Each builder gets an async accessor named after it in snake
+/// case, e.g. `app.http_client().await` for `HttpClient`, unless it is named via `HttpClient as client: transient`.
+///
+/// - The inputs of the builders are wired from the other accessors at compile time, hence a missing registration does not compile,
+///   nor does a dependency cycle, which is reported as an overflow while evaluating `Acyclic` for the builders taking part in it.
+/// - Singletons are built once by `AppContainer::new().await` in declaration order, each accessor resolving the dependencies it needs
+///   on the fly, and shared by all the accessors, while transients are built on every access, following the same lifetime semantics
+///   as `DIManager`.
+/// - Dependencies are resolved by output type, hence a builder can only be registered once, and two builders cannot share the same
+///   output, e.g. the same `#[build_as(Box<dyn Printer>)]`.
+/// - `#[di(crate = "path")]`: Specifies the path to the `yadir` crate, for crates which rename or re-export it.
+///
+/// # Example
+///
+/// ```ignore
+///
+/// container! {
+///     pub struct AppContainer {
+///         Bar: singleton,
+///         Baz: transient,
+///         Foo: transient,
+///     }
+/// }
+///
+/// let mut app = AppContainer::new().await;
+/// let foo = app.foo().await;
+/// ```
+#[proc_macro]
+pub fn container(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as StaticContainer);
+    expand_container(input)
         .unwrap_or_else(syn::Error::into_compile_error)
         .into()
 }